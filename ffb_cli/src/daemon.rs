@@ -0,0 +1,335 @@
+//! The daemon runs the jobs of the CLI on a schedule, instead of relying on a
+//! crontab.
+//!
+//! Each job is run on its own interval. The fixtures and the bets are polled
+//! faster while some games are being played, and the jobs are slowed down
+//! overnight. Several daemons can run side by side : the time each job has
+//! last been run is shared through Redis, so that a job is run once per
+//! interval whatever the number of daemons, and a Redis lock prevents a job
+//! from being run twice at the same time. The lock is extended while the job
+//! is running, and the daemon can be interrupted in the middle of a job.
+
+use crate::error::CliError;
+use crate::provider::FootballDataProvider;
+use chrono::{NaiveDate, Timelike, Utc};
+use ffb_structs::{bet, game, job_lock};
+use tokio::time::{Duration, Instant};
+
+/// The time between two checks of the jobs to run.
+const TICK: Duration = Duration::from_secs(10);
+
+/// The schedule of the daemon, all the intervals being in seconds.
+#[derive(clap::Args, Debug)]
+pub(crate) struct Schedule {
+    /// The interval between two fetches of the fixtures.
+    #[clap(long, default_value = "3600")]
+    fixtures_interval: u64,
+    /// The interval between two fetches of the odds.
+    #[clap(long, default_value = "21600")]
+    odds_interval: u64,
+    /// The interval between two indexings of the odds.
+    #[clap(long, default_value = "21600")]
+    index_interval: u64,
+    /// The interval between two validations of the bets.
+    #[clap(long, default_value = "1800")]
+    validate_bets_interval: u64,
+    /// The interval between two fetches of the news.
+    #[clap(long, default_value = "300")]
+    news_interval: u64,
+    /// The interval between two fetches of the fixtures and validations of
+    /// the bets while some games are being played.
+    #[clap(long, default_value = "120")]
+    live_interval: u64,
+    /// The hour, UTC, the night starts at.
+    #[clap(long, default_value = "1")]
+    night_start: u32,
+    /// The hour, UTC, the night ends at.
+    #[clap(long, default_value = "7")]
+    night_end: u32,
+    /// The factor the intervals are multiplied by overnight.
+    #[clap(long, default_value = "4")]
+    night_factor: u32,
+    /// The number of days ahead the fixtures and odds are fetched for.
+    #[clap(long, default_value = "0")]
+    days_ahead: i64,
+    /// The time after which the lock of a job expires.
+    #[clap(long, default_value = "900")]
+    lock_expiration: u64,
+}
+
+impl Schedule {
+    /// Whether the given hour is overnight.
+    ///
+    /// # Arguments
+    ///
+    /// - hour : the hour of the day, UTC.
+    fn is_overnight(&self, hour: u32) -> bool {
+        if self.night_start <= self.night_end {
+            self.night_start <= hour && hour < self.night_end
+        } else {
+            self.night_start <= hour || hour < self.night_end
+        }
+    }
+
+    /// Get the time to wait between two runs of a job.
+    ///
+    /// # Arguments
+    ///
+    /// - job : the job to run.
+    /// - live : whether some games are being played.
+    /// - overnight : whether it is overnight.
+    fn get_delay(&self, job: Job, live: bool, overnight: bool) -> Duration {
+        let interval: u64 = match job {
+            Job::Fixtures => self.fixtures_interval,
+            Job::Odds => self.odds_interval,
+            Job::IndexOdds => self.index_interval,
+            Job::ValidateBets => self.validate_bets_interval,
+            Job::News => self.news_interval,
+        };
+        if live && job.follows_live_games() {
+            Duration::from_secs(interval.min(self.live_interval))
+        } else if overnight {
+            Duration::from_secs(interval.saturating_mul(u64::from(self.night_factor)))
+        } else {
+            Duration::from_secs(interval)
+        }
+    }
+}
+
+/// The jobs run by the daemon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Display)]
+enum Job {
+    /// Fetch the fixtures, see [crate::Getter::Fixtures].
+    Fixtures,
+    /// Fetch the odds, see [crate::Getter::Odds].
+    Odds,
+    /// Index the odds, see [crate::Getter::IndexOdds].
+    IndexOdds,
+    /// Validate the bets, see [crate::Getter::ValidateBets].
+    ValidateBets,
+    /// Fetch the news, see [crate::Getter::News].
+    News,
+}
+
+impl Job {
+    /// All the jobs, in the order they are run.
+    const ALL: [Job; 5] = [
+        Job::Fixtures,
+        Job::ValidateBets,
+        Job::Odds,
+        Job::IndexOdds,
+        Job::News,
+    ];
+
+    /// Whether the job has to be run faster while some games are being
+    /// played.
+    fn follows_live_games(&self) -> bool {
+        matches!(self, Job::Fixtures | Job::ValidateBets)
+    }
+
+    /// Run the job.
+    ///
+    /// # Arguments
+    ///
+    /// - provider : the source of the football data.
+    /// - days_ahead : the number of days ahead the fixtures and odds are
+    ///   fetched for.
+    async fn run(
+        &self,
+        provider: &dyn FootballDataProvider,
+        days_ahead: i64,
+    ) -> Result<(), CliError> {
+        match self {
+            Job::Fixtures | Job::Odds => {
+                let from: NaiveDate = Utc::now().naive_utc().date();
+                let to: NaiveDate = from + chrono::Duration::days(days_ahead);
+                let leagues = crate::get_league_seasons(None, false).await?;
+                if *self == Job::Fixtures {
+                    crate::fetch_fixtures(provider, from, to, &leagues).await?;
+                } else {
+                    crate::fetch_odds(provider, from, to, &leagues).await?;
+                }
+            }
+            Job::IndexOdds => crate::index_odds().await?,
+            Job::ValidateBets => bet::Entity::validate_bets().await?,
+            Job::News => crate::fetch_news().await?,
+        }
+        Ok(())
+    }
+}
+
+/// Run the jobs on the schedule until the process is interrupted.
+///
+/// A job failing doesn't stop the daemon, it is run again on its next turn.
+///
+/// # Arguments
+///
+/// - provider : the source of the football data.
+/// - schedule : the schedule of the jobs.
+pub(crate) async fn run(
+    provider: &dyn FootballDataProvider,
+    schedule: &Schedule,
+) -> Result<(), CliError> {
+    info!("Daemon started with schedule {:?}", schedule);
+    loop {
+        let live: bool = game::Entity::has_live_games().await.unwrap_or_else(|err| {
+            warn!("Couldn't tell whether games are being played : {}", err);
+            false
+        });
+        let overnight: bool = schedule.is_overnight(Utc::now().hour());
+        for job in Job::ALL {
+            let delay: Duration = schedule.get_delay(job, live, overnight);
+            // The job is considered as run even if another daemon holds its
+            // lock, so that it isn't retried on every tick.
+            match job_lock::Entity::claim_turn(&job.to_string(), delay.as_secs()) {
+                Ok(true) => (),
+                Ok(false) => continue,
+                Err(err) => {
+                    error!("The turn of job {} couldn't be claimed : {}", job, err);
+                    continue;
+                }
+            }
+            if run_locked(job, provider, schedule).await? {
+                info!("Daemon interrupted");
+                return Ok(());
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(TICK) => {}
+            signal = tokio::signal::ctrl_c() => {
+                signal?;
+                info!("Daemon interrupted");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Run a job if no other process is running it.
+///
+/// The lock of the job is extended every third of its expiration while the
+/// job is running. The job is dropped if the process is interrupted.
+///
+/// Returns whether the process has been interrupted.
+///
+/// # Arguments
+///
+/// - job : the job to run.
+/// - provider : the source of the football data.
+/// - schedule : the schedule of the jobs.
+async fn run_locked(
+    job: Job,
+    provider: &dyn FootballDataProvider,
+    schedule: &Schedule,
+) -> Result<bool, CliError> {
+    let name: String = job.to_string();
+    let token: String = match job_lock::Entity::acquire(&name, schedule.lock_expiration) {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            info!("Job {} is already being run by another process", name);
+            return Ok(false);
+        }
+        Err(err) => {
+            error!("The lock of job {} couldn't be acquired : {}", name, err);
+            return Ok(false);
+        }
+    };
+    let now = Instant::now();
+    let job_run = job.run(provider, schedule.days_ahead);
+    tokio::pin!(job_run);
+    let renewal_period: Duration = Duration::from_secs((schedule.lock_expiration / 3).max(1));
+    let mut renewal = tokio::time::interval_at(now + renewal_period, renewal_period);
+    let interrupted: bool = loop {
+        tokio::select! {
+            result = &mut job_run => {
+                match result {
+                    Ok(()) => info!("Job {} done in {} seconds", name, now.elapsed().as_secs()),
+                    Err(err) => error!("Job {} failed : {}", name, err),
+                }
+                break false;
+            }
+            _ = renewal.tick() => {
+                match job_lock::Entity::extend(&name, &token, schedule.lock_expiration) {
+                    Ok(true) => debug!("The lock of job {} has been extended", name),
+                    Ok(false) => warn!("The lock of job {} has been lost while running it", name),
+                    Err(err) => error!("The lock of job {} couldn't be extended : {}", name, err),
+                }
+            }
+            signal = tokio::signal::ctrl_c() => {
+                signal?;
+                warn!("Job {} interrupted after {} seconds", name, now.elapsed().as_secs());
+                break true;
+            }
+        }
+    };
+    if let Err(err) = job_lock::Entity::release(&name, &token) {
+        error!("The lock of job {} couldn't be released : {}", name, err);
+    }
+    Ok(interrupted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Args {
+        #[clap(flatten)]
+        schedule: Schedule,
+    }
+
+    /// Build a schedule from the daemon's command line arguments.
+    fn build_schedule(args: &[&str]) -> Schedule {
+        Args::parse_from(std::iter::once("daemon").chain(args.iter().copied())).schedule
+    }
+
+    #[test]
+    fn night_within_the_day() {
+        let schedule = build_schedule(&["--night-start", "1", "--night-end", "7"]);
+        assert!(!schedule.is_overnight(0));
+        assert!(schedule.is_overnight(1));
+        assert!(schedule.is_overnight(6));
+        assert!(!schedule.is_overnight(7));
+    }
+
+    #[test]
+    fn night_over_midnight() {
+        let schedule = build_schedule(&["--night-start", "22", "--night-end", "6"]);
+        assert!(schedule.is_overnight(23));
+        assert!(schedule.is_overnight(0));
+        assert!(!schedule.is_overnight(6));
+        assert!(!schedule.is_overnight(12));
+    }
+
+    #[test]
+    fn live_games_speed_up_the_jobs_following_them() {
+        let schedule = build_schedule(&["--fixtures-interval", "3600", "--live-interval", "120"]);
+        assert_eq!(
+            schedule.get_delay(Job::Fixtures, true, true),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            schedule.get_delay(Job::Fixtures, false, false),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            schedule.get_delay(Job::News, true, false),
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn jobs_slow_down_overnight() {
+        let schedule = build_schedule(&["--odds-interval", "100", "--night-factor", "4"]);
+        assert_eq!(
+            schedule.get_delay(Job::Odds, false, true),
+            Duration::from_secs(400)
+        );
+        let schedule = build_schedule(&["--odds-interval", &u64::MAX.to_string()]);
+        assert_eq!(
+            schedule.get_delay(Job::Odds, false, true),
+            Duration::from_secs(u64::MAX)
+        );
+    }
+}
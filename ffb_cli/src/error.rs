@@ -26,6 +26,11 @@ pub enum CliError {
     /// When no main bookmaker is set
     #[display(fmt = "No main bookmaker has been set, set one before fetching the odds")]
     NoMainBookmaker,
+    /// When the current season of none of the leagues to fetch is known
+    #[display(
+        fmt = "The current season of none of the leagues to fetch is known, fetch the leagues first"
+    )]
+    NoLeagueSeason,
 }
 
 impl Termination for CliError {
@@ -38,6 +43,7 @@ impl Termination for CliError {
             CliError::UrlError(_) => ExitCode::from(14),
             CliError::SerdeErr(_) => ExitCode::from(15),
             CliError::NoMainBookmaker => ExitCode::from(16),
+            CliError::NoLeagueSeason => ExitCode::from(17),
         }
     }
 }
@@ -2,18 +2,25 @@
 //! remote endpoints or existing local data.
 //!
 //! These commands have to be used with the help of a crontab and need to be
-//! executed regulary in order to keep the application data up to date.
+//! executed regulary in order to keep the application data up to date, or be
+//! run on a schedule by the daemon command.
 
 use async_std::{
     fs::File,
     io::{copy, Cursor},
 };
-use chrono::{DateTime, Utc};
+use chrono::{NaiveDate, Utc};
 use clap::{Parser, Subcommand};
 use dotenv::dotenv;
 use error::CliError;
-use ffb_structs::{api_token, bet, bookmaker, club, game, info, info::Model as Info, league, odd};
+use ffb_structs::{
+    api_token, bet, bookmaker, club, game, info, info::Model as Info, league, odd,
+    scoreboard_snapshot,
+};
+use provider::{api_football::Recording, FootballDataProvider, LeagueSeason, PlannedFetch};
 use scraper::{Html, Selector};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::process::{ExitCode, Termination};
 use url::Url;
 
@@ -23,18 +30,33 @@ extern crate log;
 #[macro_use]
 extern crate derive_more;
 
+/// The scheduler running the commands in the background.
+pub(crate) mod daemon;
 /// Crate to handle common applicative errors.
 pub(crate) mod error;
+/// The sources of the football data.
+pub(crate) mod provider;
 
 /// Cli arguments,
 /// One getter is defined so far,
-/// no options are available.
+/// the responses of the API can be recorded or replayed.
 #[derive(Parser, Debug)]
 struct Args {
     /// The getter is the list of models that
     /// can be fetched.
     #[clap(subcommand)]
     get: Getter,
+    /// Save every response of the API within the given directory.
+    #[clap(long, global = true, value_name = "DIR")]
+    record: Option<PathBuf>,
+    /// Read the responses of the API from the given directory, as they have
+    /// been recorded, instead of calling it.
+    #[clap(long, global = true, value_name = "DIR", conflicts_with = "record")]
+    replay: Option<PathBuf>,
+    /// Estimate how many calls to the API the command costs, against the
+    /// calls remaining today, instead of running it.
+    #[clap(long, global = true)]
+    dry_run: bool,
 }
 
 /// The getter subcommand defines which model
@@ -56,15 +78,8 @@ enum Getter {
         indexable: Indexable,
     },
     Fixtures {
-        /// The day difference
-        ///
-        /// This fetches if nothing is passed or
-        /// equals to 0, the games of the day
-        /// will be fetched, if it is equals to
-        /// -1, the days of yesterday will be fetched,
-        /// and so on.
-        #[clap(default_value = "0")]
-        day_diff: i64,
+        #[clap(flatten)]
+        scope: FetchScope,
     },
     /// Index the odds.
     ///
@@ -76,30 +91,135 @@ enum Getter {
     /// This arg is meant give points of the betters if they predicted
     /// correctly.
     ValidateBets,
+    /// Settle again the bets whose game score has been corrected.
+    ///
+    /// This arg is meant to be used after the fixtures have been fetched, so
+    /// that the scores corrected by the API provider are reported on the
+    /// users' points.
+    ResettleBets,
+    /// Snapshot the scoreboard of the current season.
+    ///
+    /// This arg is meant to be used once a day, so that the change of ranking
+    /// of the users and its evolution over the season can be displayed.
+    SnapshotScoreboard,
     /// Fetching the odds.
     ///
     /// This adds the probabilities to win of each teams
     /// to the fixtures, so that the users can bet easily.
     Odds {
-        /// Day diff, similar to [Getter::Fixtures::day_diff]
-        #[clap(default_value = "0")]
-        day_diff: i64,
+        #[clap(flatten)]
+        scope: FetchScope,
     },
     /// Fetch the bookmaker (or the source of the odds).
     Bookmakers,
-    /// Add an api token to the list.
+    /// Manage the list of api tokens.
     ///
     /// The model of usage of the API tokens is to use always the one with the
     /// most remaining calls. Since the API I am using is a freemium, the goal
     /// is to use register several and use them all without going through the
     /// threshold.
+    ///
+    /// `api-token <TOKEN>` is still accepted as a shorthand for
+    /// `api-token add <TOKEN>`.
+    #[clap(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
     ApiToken {
-        /// The token to add to the list of tokens.
-        token: String,
+        /// The token to add to the list of tokens, with the default daily
+        /// quota.
+        token: Option<String>,
+        #[clap(subcommand)]
+        action: Option<ApiTokenAction>,
     },
     /// Fetch the latest transfer news. The source is the soccer subreddit,
     /// the frequency can be from every 1 to every 5 minutes.
     News,
+    /// Run the fixtures, odds, index, validate-bets and news commands on a
+    /// schedule, until the process is interrupted.
+    Daemon(daemon::Schedule),
+}
+
+/// The actions on the api tokens.
+#[derive(Subcommand, Debug)]
+enum ApiTokenAction {
+    /// Add an api token to the list.
+    Add {
+        /// The token to add to the list of tokens.
+        token: String,
+        /// The number of calls that can be done with the token each day.
+        #[clap(long, default_value_t = api_token::DEFAULT_DAILY_QUOTA)]
+        daily_quota: i32,
+    },
+    /// List the api tokens, along with their calls remaining.
+    List,
+    /// Remove an api token from the list.
+    Remove {
+        /// The token to remove.
+        token: String,
+    },
+    /// Disable an api token, so that it isn't used until it is reset.
+    Disable {
+        /// The token to disable.
+        token: String,
+    },
+    /// Reset the calls remaining of the api tokens to their daily quota.
+    ///
+    /// This is done on its own every day, a disabled token is enabled again
+    /// only when it is reset explicitly.
+    Reset {
+        /// The token to reset, all the tokens in use if none.
+        token: Option<String>,
+    },
+}
+
+/// The days and leagues the fixtures or the odds are fetched for.
+#[derive(clap::Args, Debug)]
+struct FetchScope {
+    /// The day difference
+    ///
+    /// This fetches if nothing is passed or
+    /// equals to 0, the games of the day
+    /// will be fetched, if it is equals to
+    /// -1, the days of yesterday will be fetched,
+    /// and so on.
+    #[clap(default_value = "0")]
+    day_diff: i64,
+    /// The first day to fetch, as YYYY-MM-DD.
+    ///
+    /// Takes precedence over the day difference.
+    #[clap(long)]
+    from: Option<NaiveDate>,
+    /// The last day to fetch, as YYYY-MM-DD.
+    ///
+    /// Defaults to the first day to fetch.
+    #[clap(long)]
+    to: Option<NaiveDate>,
+    /// The ids of the leagues to fetch, separated by commas.
+    ///
+    /// Defaults to the favorite leagues of the users and the leagues of the
+    /// games that have been open to bets.
+    #[clap(long, use_value_delimiter = true)]
+    leagues: Option<Vec<u32>>,
+    /// Fetch every league, whatever the leagues followed are.
+    #[clap(long, conflicts_with = "leagues")]
+    all_leagues: bool,
+}
+
+impl FetchScope {
+    /// Get the first and last days to fetch.
+    fn get_days(&self) -> Result<(NaiveDate, NaiveDate), CliError> {
+        let from: NaiveDate = self.from.unwrap_or_else(|| {
+            (Utc::now() + chrono::Duration::days(self.day_diff))
+                .naive_utc()
+                .date()
+        });
+        let to: NaiveDate = self.to.unwrap_or(from);
+        if to < from {
+            return Err(CliError::VarError(format!(
+                "The last day {} is before the first day {}",
+                to, from
+            )));
+        }
+        Ok((from, to))
+    }
 }
 
 /// A fetchable struct is a remote structure from the API Provider.
@@ -154,9 +274,19 @@ async fn run_main() -> Result<(), CliError> {
 
     let args = Args::parse();
     debug!("Args parsed : {:#?}", args);
+    let recording: Option<Recording> = match (args.record, args.replay) {
+        (Some(dir), _) => Some(Recording::Record(dir)),
+        (None, Some(dir)) => Some(Recording::Replay(dir)),
+        (None, None) => None,
+    };
+    // The provider is only built by the commands using it, so that the other
+    // ones don't depend on its configuration.
+    if args.dry_run {
+        return estimate_calls(recording, &args.get).await;
+    }
     match args.get {
         Getter::Leagues { indexable } => match indexable {
-            Indexable::Model => fetch_leagues().await?,
+            Indexable::Model => fetch_leagues(provider::from_env(recording)?.as_ref()).await?,
             Indexable::Logo => fetch_leagues_logo().await?,
             Indexable::Index => league::Entity::index().await?,
         },
@@ -165,13 +295,140 @@ async fn run_main() -> Result<(), CliError> {
             Indexable::Logo => fetch_clubs_logo().await?,
             Indexable::Index => index_clubs().await?,
         },
-        Getter::Fixtures { day_diff } => fetch_fixtures(day_diff).await?,
-        Getter::Bookmakers => fetch_bookmakers().await?,
-        Getter::ApiToken { token } => api_token::Entity::register(&token)?,
-        Getter::Odds { day_diff } => fetch_odds(day_diff).await?,
-        Getter::IndexOdds => odd::Entity::index().await?,
+        Getter::Fixtures { scope } => {
+            let (from, to) = scope.get_days()?;
+            let leagues = get_league_seasons(scope.leagues, scope.all_leagues).await?;
+            fetch_fixtures(provider::from_env(recording)?.as_ref(), from, to, &leagues).await?
+        }
+        Getter::Bookmakers => fetch_bookmakers(provider::from_env(recording)?.as_ref()).await?,
+        Getter::ApiToken { token, action } => match (token, action) {
+            (_, Some(action)) => manage_api_tokens(action)?,
+            (Some(token), None) => manage_api_tokens(ApiTokenAction::Add {
+                token,
+                daily_quota: api_token::DEFAULT_DAILY_QUOTA,
+            })?,
+            (None, None) => {
+                return Err(CliError::VarError(
+                    "A token or an action on the tokens is required".into(),
+                ))
+            }
+        },
+        Getter::Odds { scope } => {
+            let (from, to) = scope.get_days()?;
+            let leagues = get_league_seasons(scope.leagues, scope.all_leagues).await?;
+            fetch_odds(provider::from_env(recording)?.as_ref(), from, to, &leagues).await?
+        }
+        Getter::IndexOdds => index_odds().await?,
         Getter::News => fetch_news().await?,
         Getter::ValidateBets => bet::Entity::validate_bets().await?,
+        Getter::ResettleBets => bet::Entity::resettle_bets().await?,
+        Getter::SnapshotScoreboard => scoreboard_snapshot::Entity::take_snapshot().await?,
+        Getter::Daemon(schedule) => {
+            daemon::run(provider::from_env(recording)?.as_ref(), &schedule).await?
+        }
+    }
+    Ok(())
+}
+
+/// Run an action on the api tokens.
+///
+/// Has to be called with [Getter::ApiToken].
+///
+/// # Arguments
+///
+/// - action : the action to run.
+fn manage_api_tokens(action: ApiTokenAction) -> Result<(), CliError> {
+    match action {
+        ApiTokenAction::Add { token, daily_quota } => {
+            api_token::Entity::register(&token, daily_quota)?
+        }
+        ApiTokenAction::List => {
+            for model in api_token::Entity::get_all()? {
+                match (model.remaining_calls, model.disabled_reason) {
+                    (Some(remaining_calls), _) => println!(
+                        "{} : {}/{} calls remaining",
+                        model.token, remaining_calls, model.daily_quota
+                    ),
+                    (None, reason) => println!(
+                        "{} : disabled ({})",
+                        model.token,
+                        reason.unwrap_or_default()
+                    ),
+                }
+            }
+        }
+        ApiTokenAction::Remove { token } => {
+            if !api_token::Entity::remove(&token)? {
+                warn!("The token {} isn't registered", token);
+            }
+        }
+        ApiTokenAction::Disable { token } => {
+            if !api_token::Entity::disable(&token, "Disabled manually")? {
+                warn!("The token {} isn't in use", token);
+            }
+        }
+        ApiTokenAction::Reset { token: Some(token) } => {
+            if !api_token::Entity::reset(&token)? {
+                warn!("The token {} isn't registered", token);
+            }
+        }
+        ApiTokenAction::Reset { token: None } => api_token::Entity::reset_all()?,
+    }
+    Ok(())
+}
+
+/// Estimate how many calls to the API a command costs, and compare it to the
+/// calls that can still be done today.
+///
+/// Is called with the `--dry-run` flag, the command isn't run.
+///
+/// # Arguments
+///
+/// - recording : Whether the responses of the API are recorded or replayed.
+/// - getter : The command whose cost is estimated.
+async fn estimate_calls(recording: Option<Recording>, getter: &Getter) -> Result<(), CliError> {
+    let fetch: PlannedFetch = match getter {
+        Getter::Leagues {
+            indexable: Indexable::Model,
+        } => PlannedFetch::Leagues,
+        Getter::Bookmakers => PlannedFetch::Bookmakers,
+        Getter::Fixtures { scope } | Getter::Odds { scope } => {
+            let (from, to) = scope.get_days()?;
+            let days: u64 = provider::get_days(from, to).len() as u64;
+            let leagues: Option<u64> = get_league_seasons(scope.leagues.clone(), scope.all_leagues)
+                .await?
+                .map(|leagues| leagues.len() as u64);
+            match getter {
+                Getter::Fixtures { .. } => PlannedFetch::Fixtures { days, leagues },
+                _ => PlannedFetch::Odds { days, leagues },
+            }
+        }
+        Getter::Daemon(_) => {
+            println!("The calls of the daemon depend on its schedule and can't be estimated");
+            return Ok(());
+        }
+        _ => {
+            println!("The command doesn't call the API");
+            return Ok(());
+        }
+    };
+    let calls: u64 = provider::from_env(recording)?.estimate_calls(fetch);
+    if calls == 0 {
+        println!("The command doesn't call the API");
+        return Ok(());
+    }
+    let remaining_calls: i64 = api_token::Entity::get_remaining_calls()?;
+    // The number of pages of the odds is only known once they are fetched.
+    let qualifier: &str = match fetch {
+        PlannedFetch::Odds { .. } => "at least ",
+        _ => "",
+    };
+    println!(
+        "The command costs {}{} calls, {} calls remaining today",
+        qualifier, calls, remaining_calls
+    );
+    if remaining_calls < calls as i64 {
+        println!("The calls remaining today aren't enough to run the command");
     }
     Ok(())
 }
@@ -208,22 +465,14 @@ async fn fetch_news() -> Result<(), CliError> {
     Ok(())
 }
 
-/// Fetch the leagues from the API provider.
+/// Fetch the leagues from the data provider.
 ///
 /// Has to be called with [Getter::Leagues] variant [Indexable::Model].
-async fn fetch_leagues() -> Result<(), CliError> {
+async fn fetch_leagues(provider: &dyn FootballDataProvider) -> Result<(), CliError> {
     debug!("Fetch leagues called");
-    let res = call_api_endpoint("leagues".into()).await?;
-    let mut storable: Vec<serde_json::Value> = Vec::new();
-    for elt in res["response"].as_array().ok_or_else(|| {
-        CliError::RequestError("Data received in the wrong format for the server".into())
-    })? {
-        // The response contains both the league and country information,
-        // the country information is ignored by this process.
-        storable.push(elt["league"].clone());
-    }
+    let leagues: Vec<league::Model> = provider.get_leagues().await?;
     debug!("League entity successfully retrieved from response");
-    league::Entity::store(&serde_json::to_string(&storable)?).await?;
+    league::Entity::store(leagues).await?;
     debug!("League entity stored");
     Ok(())
 }
@@ -330,61 +579,156 @@ async fn bulk_download_files(files_uri: Vec<String>) -> Result<(), CliError> {
 
 /// Fetch the bookmakers.
 /// Has to be called with [Getter::Bookmakers].
-async fn fetch_bookmakers() -> Result<(), CliError> {
-    let res = call_api_endpoint("odds/bookmakers".into()).await?;
-    let response: String = res["response"].to_string();
+async fn fetch_bookmakers(provider: &dyn FootballDataProvider) -> Result<(), CliError> {
+    let bookmakers: Vec<bookmaker::Model> = provider.get_bookmakers().await?;
     debug!("Response to bookmaker endpoint is succesful, entity will be stored.");
-    bookmaker::Entity::store(&response).await?;
+    bookmaker::Entity::store(bookmakers).await?;
     debug!("Bookmakers stored");
     Ok(())
 }
 
-/// Fetch the odds associed with each fixtures for the given day_diff.
+/// Index the stored odds and report the fixtures that have been skipped.
+///
+/// Has to be called with [Getter::IndexOdds].
+async fn index_odds() -> Result<(), CliError> {
+    let report: odd::IndexReport = odd::Entity::index().await?;
+    for skipped in &report.skipped {
+        warn!(
+            "The odds of fixture {} have been skipped : {}",
+            skipped.fixture_id, skipped.reason
+        );
+    }
+    info!(
+        "{} fixtures indexed, {} skipped",
+        report.indexed.len(),
+        report.skipped.len()
+    );
+    Ok(())
+}
+
+/// Get the seasons of the leagues to restrict a fetch to.
+///
+/// Returns none if every league has to be fetched. Fails if the current
+/// season of none of the leagues is known, rather than fetching nothing.
+///
+/// # Arguments
+///
+/// - leagues : the ids of the leagues to fetch, the leagues followed within
+///   the application if none.
+/// - all_leagues : whether to fetch every league.
+async fn get_league_seasons(
+    leagues: Option<Vec<u32>>,
+    all_leagues: bool,
+) -> Result<Option<Vec<LeagueSeason>>, CliError> {
+    if all_leagues {
+        return Ok(None);
+    }
+    let league_ids: Vec<u32> = match leagues {
+        Some(league_ids) => league_ids,
+        None => league::Entity::get_followed_ids().await?,
+    };
+    // Otherwise no fixture could ever be open to bets on a new instance.
+    if league_ids.is_empty() {
+        warn!("No league is followed yet, every league will be fetched");
+        return Ok(None);
+    }
+    let current_seasons: HashMap<u32, u32> =
+        league::Entity::get_current_seasons(&league_ids).await?;
+    let mut league_seasons: Vec<LeagueSeason> = Vec::with_capacity(league_ids.len());
+    for league_id in league_ids {
+        match current_seasons.get(&league_id) {
+            Some(season) => league_seasons.push(LeagueSeason {
+                league_id,
+                season: *season,
+            }),
+            None => warn!(
+                "The current season of league {} is unknown, the leagues have to be fetched first",
+                league_id
+            ),
+        }
+    }
+    if league_seasons.is_empty() {
+        return Err(CliError::NoLeagueSeason);
+    }
+    Ok(Some(league_seasons))
+}
+
+/// Fetch the odds associed with each fixtures for the given days.
+///
+/// The odds are fetched from the main bookmaker. The upcoming fixtures of the
+/// leagues it has no odds for are then fetched from all the bookmakers, so
+/// that the indexing can fall back on another one.
 ///
 /// Has to be called with [Getter::Odds].
 ///
 /// # Arguments
 ///
-/// - day_diff : The day we want to fetch the odds of the fixtures, as a
-/// difference of today (ie. yesteday = -1, tomorow =1, today =0, ...).
-async fn fetch_odds(day_diff: i64) -> Result<(), CliError> {
-    let now: DateTime<Utc> = Utc::now();
-    let date_diff: DateTime<Utc> = now + chrono::Duration::days(day_diff);
-    let mut date_to_fetch = date_diff.to_rfc3339();
-    // RFC 3339 format has to be truncated of its last 10 chars to obtain
-    // a date such `YYYY-MM-DD`
-    date_to_fetch.truncate(10);
+/// - provider : The source of the odds.
+/// - from : The first day of the fixtures to fetch the odds of.
+/// - to : The last day of the fixtures to fetch the odds of.
+/// - leagues : The leagues to fetch the odds of, every league if none.
+async fn fetch_odds(
+    provider: &dyn FootballDataProvider,
+    from: NaiveDate,
+    to: NaiveDate,
+    leagues: &Option<Vec<LeagueSeason>>,
+) -> Result<(), CliError> {
     let main_bookmaker_id: u32 = bookmaker::Entity::get_main_bookmaker_id()
         .await?
         .ok_or(CliError::NoMainBookmaker)?;
-    // The result is most of the time paginated, so we need to ensure to get the
-    // results from each page, the number of page is unknwon before making the
-    // first call, so we need to loop until our page number equals the last one
-    let mut page: u64 = 1;
-    loop {
-        info!(
-            "Page {} being called for bookmaker id {} and date {}",
-            page, main_bookmaker_id, date_to_fetch
-        );
-        let res = call_api_endpoint(format!(
-            "odds?date={}&bookmaker={}&bet=1&page={}",
-            date_to_fetch, main_bookmaker_id, page
-        ))
-        .await?;
-        debug!(
-            "Remoe end point called successfully for page number #{}",
-            page
-        );
-        let response: String = res["response"].to_string();
-        let total_pages: Option<u64> = res["paging"]["total"].as_u64();
-        // We then store the odds, who are as String
-        odd::Entity::store(&response).await?;
-        match total_pages {
-            Some(v) if v != page => {
-                info!("Page {}/{} successfully stored", page, v);
-                page += 1;
+    let league_filters: Vec<Option<LeagueSeason>> = match leagues {
+        Some(leagues) => leagues.iter().copied().map(Some).collect(),
+        None => vec![None],
+    };
+    for date_to_fetch in provider::get_days(from, to) {
+        for league in &league_filters {
+            let mut fetched_fixture_ids: HashSet<u32> = HashSet::new();
+            // The result is most of the time paginated, so we need to ensure to get the
+            // results from each page, the number of page is unknwon before making the
+            // first call, so we need to loop until our page number equals the last one
+            let mut page: u64 = 1;
+            loop {
+                info!(
+                    "Page {} being called for date {} and league {:?} (main bookmaker id {})",
+                    page, date_to_fetch, league, main_bookmaker_id
+                );
+                let odds_page = provider
+                    .get_odds(date_to_fetch, *league, Some(main_bookmaker_id), page)
+                    .await?;
+                debug!(
+                    "Remoe end point called successfully for page number #{}",
+                    page
+                );
+                fetched_fixture_ids.extend(odds_page.odds.iter().map(odd::Model::get_fixture_id));
+                // We then store the odds of the page
+                odd::Entity::store(odds_page.odds).await?;
+                if page < odds_page.total_pages {
+                    info!(
+                        "Page {}/{} successfully stored",
+                        page, odds_page.total_pages
+                    );
+                    page += 1;
+                } else {
+                    break;
+                }
+            }
+            // The fallback costs a call per fixture, it is only run on the
+            // leagues the fetch is restricted to.
+            if let Some(league) = league {
+                for fixture_id in
+                    game::Entity::get_upcoming_fixture_ids(date_to_fetch, league.league_id).await?
+                {
+                    if fetched_fixture_ids.contains(&fixture_id) {
+                        continue;
+                    }
+                    info!(
+                        "The main bookmaker has no odds for fixture {}, the other bookmakers are called",
+                        fixture_id
+                    );
+                    let odds = provider.get_fixture_odds(date_to_fetch, fixture_id).await?;
+                    odd::Entity::store(odds).await?;
+                }
             }
-            _ => break,
         }
     }
     debug!("Odds stored");
@@ -393,55 +737,96 @@ async fn fetch_odds(day_diff: i64) -> Result<(), CliError> {
 
 /// Fetch the remote fixtures.
 ///
-/// Has to be called with [Getter::Fixtures] variant [Fetchable::Model].
-async fn fetch_fixtures(day_diff: i64) -> Result<(), CliError> {
-    let now = chrono::Utc::now();
-    let mut date_to_fetch = (now + chrono::Duration::days(day_diff)).to_rfc3339();
-    date_to_fetch.truncate(10);
-    debug!("Date fetched : {}", date_to_fetch);
-    let res = call_api_endpoint(format!("fixtures?date={}", &date_to_fetch)).await?;
-    let response: String = res["response"].to_string();
-    game::Entity::store(&date_to_fetch, &response).await?;
+/// The fixtures are stored day by day, so that the last time each day has
+/// been fetched is kept.
+///
+/// Has to be called with [Getter::Fixtures].
+///
+/// # Arguments
+///
+/// - provider : The source of the fixtures.
+/// - from : The first day of the fixtures to fetch.
+/// - to : The last day of the fixtures to fetch.
+/// - leagues : The leagues to fetch the fixtures of, every league if none.
+async fn fetch_fixtures(
+    provider: &dyn FootballDataProvider,
+    from: NaiveDate,
+    to: NaiveDate,
+    leagues: &Option<Vec<LeagueSeason>>,
+) -> Result<(), CliError> {
+    debug!("Dates fetched : {} to {}", from, to);
+    let fixtures: Vec<game::Model> = match leagues {
+        Some(leagues) => {
+            let mut fixtures: Vec<game::Model> = Vec::new();
+            for league in leagues {
+                fixtures.extend(provider.get_fixtures(from, to, Some(*league)).await?);
+            }
+            fixtures
+        }
+        None => provider.get_fixtures(from, to, None).await?,
+    };
+    let mut fixtures_by_date: BTreeMap<NaiveDate, Vec<game::Model>> = provider::get_days(from, to)
+        .into_iter()
+        .map(|date| (date, Vec::new()))
+        .collect();
+    for fixture in fixtures {
+        let date: NaiveDate = fixture.fixture.date.naive_utc().date();
+        match fixtures_by_date.get_mut(&date) {
+            Some(fixtures) => fixtures.push(fixture),
+            None => warn!(
+                "The fixture {} isn't played within the fetched days",
+                fixture.fixture.id
+            ),
+        }
+    }
+    for (date, fixtures) in fixtures_by_date {
+        game::Entity::store(&date.to_string(), fixtures).await?;
+    }
     debug!("Games stored");
     Ok(())
 }
 
-/// Calls the remote API endpoint.
-///
-/// Be aware that it is using the `API_PROVIDER` environment variable.
-///
-/// The final URL that will be called will then be `API_PROVIDER` + `endpoint`.
-///
-/// # Arguments :
-/// * endpoint : The endpoint to call, the endpoint.
-async fn call_api_endpoint(endpoint: String) -> Result<serde_json::Value, CliError> {
-    let client = reqwest::Client::builder().build()?;
-    let token: String = api_token::Entity::get_token()?;
-    info!("Endpoint called : {}", endpoint.as_str());
-    let res = client
-        .get(std::env::var("API_PROVIDER")? + endpoint.as_str())
-        .header("x-rapidapi-host", "api-football-v1.p.rapidapi.com")
-        .header("x-rapidapi-key", &token)
-        .send()
-        .await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Part to know whether there are still calls to be made with this token
-    if let Some(rem) = res.headers().get("X-RateLimit-requests-Remaining") {
-        let remaining_calls: i32 = rem.to_str().unwrap().parse()?;
-        info!(
-            "Number of calls remaining for token {} : {}",
-            &token, remaining_calls
+    fn build_scope(day_diff: i64, from: Option<NaiveDate>, to: Option<NaiveDate>) -> FetchScope {
+        FetchScope {
+            day_diff,
+            from,
+            to,
+            leagues: None,
+            all_leagues: false,
+        }
+    }
+
+    #[test]
+    fn scope_defaults_to_a_single_day() {
+        let today: NaiveDate = Utc::now().naive_utc().date();
+        let (from, to) = build_scope(0, None, None).get_days().unwrap();
+        assert_eq!((from, to), (today, today));
+        let (from, to) = build_scope(-1, None, None).get_days().unwrap();
+        assert_eq!((from, to), (today.pred(), today.pred()));
+    }
+
+    #[test]
+    fn scope_dates_take_precedence_over_the_day_difference() {
+        let from = NaiveDate::from_ymd(2022, 4, 1);
+        let to = NaiveDate::from_ymd(2022, 4, 3);
+        assert_eq!(
+            build_scope(5, Some(from), Some(to)).get_days().unwrap(),
+            (from, to)
         );
-        api_token::Entity::update_threshold(&token, remaining_calls)?;
-    } else {
-        warn!(
-            "The number of calls remaining for the token {} couldn't have been determined",
-            &token
+        assert_eq!(
+            build_scope(5, Some(from), None).get_days().unwrap(),
+            (from, from)
         );
     }
 
-    let value: serde_json::Value = res.json::<serde_json::Value>().await?;
-    info!("Endpoint successfully reached");
-    trace!("Response : {:#?}", value);
-    Ok(value)
+    #[test]
+    fn scope_ending_before_it_starts_is_rejected() {
+        let from = NaiveDate::from_ymd(2022, 4, 3);
+        let to = NaiveDate::from_ymd(2022, 4, 1);
+        assert!(build_scope(0, Some(from), Some(to)).get_days().is_err());
+    }
 }
@@ -0,0 +1,435 @@
+use super::{FootballDataProvider, LeagueSeason, OddsPage, PlannedFetch};
+use crate::error::CliError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use ffb_structs::{api_token, bookmaker, game, league, odd};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What to do with the responses of the API.
+#[derive(Debug, Clone)]
+pub(crate) enum Recording {
+    /// Save every response received within the directory.
+    Record(PathBuf),
+    /// Read the responses from the directory instead of calling the API.
+    Replay(PathBuf),
+}
+
+/// A response of the API as it is recorded.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    /// The endpoint called.
+    endpoint: String,
+    /// The HTTP status of the response.
+    status: u16,
+    /// The headers of the response, such as the remaining calls of the token.
+    headers: BTreeMap<String, String>,
+    /// The body of the response, containing the paging information.
+    body: Value,
+}
+
+/// The api-football provider.
+///
+/// The calls are made with the registered API tokens, the one with the most
+/// remaining calls being used.
+///
+/// The responses can be recorded to be replayed later on, without any network
+/// or token use.
+#[derive(Debug, Default)]
+pub(crate) struct ApiFootball {
+    /// Whether to record or replay the responses.
+    recording: Option<Recording>,
+    /// The number of times each endpoint has been called, so that the
+    /// responses of the same endpoint are recorded and replayed in order.
+    calls: Mutex<HashMap<String, usize>>,
+}
+
+impl ApiFootball {
+    /// Create the provider.
+    ///
+    /// # Arguments
+    ///
+    /// - recording : whether to record or replay the responses.
+    pub(crate) fn new(recording: Option<Recording>) -> Self {
+        Self {
+            recording,
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the path of the recording of an endpoint.
+    ///
+    /// The first call of an endpoint is recorded without suffix, the next
+    /// ones are suffixed with their rank.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the recordings.
+    /// - endpoint : the endpoint called.
+    /// - rank : the rank of the call among the calls of the endpoint,
+    ///   starting from 1.
+    fn get_recording_path(dir: &Path, endpoint: &str, rank: usize) -> PathBuf {
+        let file_name: String = endpoint
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '=' => c,
+                _ => '_',
+            })
+            .collect();
+        match rank {
+            1 => dir.join(format!("{}.json", file_name)),
+            _ => dir.join(format!("{}.{}.json", file_name, rank)),
+        }
+    }
+
+    /// Count a call of an endpoint.
+    ///
+    /// Returns the rank of the call among the calls of the endpoint, starting
+    /// from 1.
+    ///
+    /// # Arguments
+    ///
+    /// - endpoint : the endpoint called.
+    fn count_call(&self, endpoint: &str) -> usize {
+        let mut calls = self
+            .calls
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let rank: &mut usize = calls.entry(endpoint.to_string()).or_insert(0);
+        *rank += 1;
+        *rank
+    }
+
+    /// Fail if the API hasn't answered with a success.
+    ///
+    /// # Arguments
+    ///
+    /// - endpoint : the endpoint called.
+    /// - status : the HTTP status of the response.
+    fn check_status(endpoint: &str, status: u16) -> Result<(), CliError> {
+        match StatusCode::from_u16(status) {
+            Ok(status) if status.is_success() => Ok(()),
+            _ => Err(CliError::RequestError(format!(
+                "The endpoint {} answered with status {}",
+                endpoint, status
+            ))),
+        }
+    }
+
+    /// Read the recorded response of an endpoint.
+    ///
+    /// The recorded status is honoured, a response that wasn't a success
+    /// fails as it did when it was recorded.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the recordings.
+    /// - endpoint : the endpoint called.
+    /// - rank : the rank of the call among the calls of the endpoint.
+    async fn replay(dir: &Path, endpoint: &str, rank: usize) -> Result<Value, CliError> {
+        let path: PathBuf = Self::get_recording_path(dir, endpoint, rank);
+        info!("Endpoint replayed : {} from {}", endpoint, path.display());
+        let content: String = async_std::fs::read_to_string(&path).await?;
+        let recorded: RecordedResponse = serde_json::from_str(&content)?;
+        trace!("Response : {:#?}", recorded.body);
+        Self::check_status(endpoint, recorded.status)?;
+        Ok(recorded.body)
+    }
+
+    /// Save a response within the directory of the recordings.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the recordings.
+    /// - recorded : the response to save.
+    /// - rank : the rank of the call among the calls of the endpoint.
+    async fn record(dir: &Path, recorded: &RecordedResponse, rank: usize) -> Result<(), CliError> {
+        async_std::fs::create_dir_all(dir).await?;
+        let path: PathBuf = Self::get_recording_path(dir, &recorded.endpoint, rank);
+        async_std::fs::write(&path, serde_json::to_string_pretty(recorded)?).await?;
+        info!("Response recorded within {}", path.display());
+        Ok(())
+    }
+
+    /// Calls the remote API endpoint.
+    ///
+    /// Be aware that it is using the `API_PROVIDER` environment variable.
+    ///
+    /// The final URL that will be called will then be `API_PROVIDER` +
+    /// `endpoint`.
+    ///
+    /// When replaying, the recorded response is returned instead.
+    ///
+    /// A token rejected by the API is disabled, and the next one is used. A
+    /// response that isn't a success fails the call, once it is recorded.
+    ///
+    /// # Arguments :
+    /// * endpoint : The endpoint to call, the endpoint.
+    async fn call_endpoint(&self, endpoint: String) -> Result<Value, CliError> {
+        let rank: usize = self.count_call(&endpoint);
+        if let Some(Recording::Replay(dir)) = &self.recording {
+            return Self::replay(dir, &endpoint, rank).await;
+        }
+        let client = reqwest::Client::builder().build()?;
+        // A token rejected by the API is disabled, and the call is made again
+        // with the next one until none is left.
+        let (token, res) = loop {
+            let token: String = api_token::Entity::get_token()?;
+            info!("Endpoint called : {}", endpoint.as_str());
+            let res = client
+                .get(std::env::var("API_PROVIDER")? + endpoint.as_str())
+                .header("x-rapidapi-host", "api-football-v1.p.rapidapi.com")
+                .header("x-rapidapi-key", &token)
+                .send()
+                .await?;
+            match res.status() {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    api_token::Entity::disable(
+                        &token,
+                        &format!("Rejected by the API with status {}", res.status()),
+                    )?;
+                }
+                _ => break (token, res),
+            }
+        };
+
+        // Part to know whether there are still calls to be made with this token
+        if let Some(rem) = res.headers().get("X-RateLimit-requests-Remaining") {
+            let remaining_calls: i32 = rem.to_str().unwrap().parse()?;
+            info!(
+                "Number of calls remaining for token {} : {}",
+                &token, remaining_calls
+            );
+            api_token::Entity::update_threshold(&token, remaining_calls)?;
+        } else {
+            warn!(
+                "The number of calls remaining for the token {} couldn't have been determined",
+                &token
+            );
+        }
+
+        let status: u16 = res.status().as_u16();
+        let headers: BTreeMap<String, String> = res
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        let value: Value = res.json::<Value>().await?;
+        info!("Endpoint successfully reached");
+        trace!("Response : {:#?}", value);
+        if let Some(Recording::Record(dir)) = &self.recording {
+            let recorded = RecordedResponse {
+                endpoint,
+                status,
+                headers,
+                body: value,
+            };
+            Self::record(dir, &recorded, rank).await?;
+            Self::check_status(&recorded.endpoint, status)?;
+            return Ok(recorded.body);
+        }
+        Self::check_status(&endpoint, status)?;
+        Ok(value)
+    }
+
+    /// Get the response's data of a call to the API.
+    ///
+    /// # Arguments
+    ///
+    /// - res : the body returned by the API.
+    fn get_response(res: &Value) -> Result<Vec<Value>, CliError> {
+        res["response"].as_array().cloned().ok_or_else(|| {
+            CliError::RequestError("Data received in the wrong format for the server".into())
+        })
+    }
+
+    /// Get the response's data of a call to the API as models.
+    ///
+    /// # Arguments
+    ///
+    /// - res : the body returned by the API.
+    fn get_models<T: DeserializeOwned>(res: &Value) -> Result<Vec<T>, CliError> {
+        Self::get_response(res)?
+            .into_iter()
+            .map(|model| Ok(serde_json::from_value(model)?))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl FootballDataProvider for ApiFootball {
+    async fn get_leagues(&self) -> Result<Vec<league::Model>, CliError> {
+        let res = self.call_endpoint("leagues".into()).await?;
+        // The response contains both the league and country information,
+        // the country information is ignored by this process.
+        let mut leagues: Vec<league::Model> = Vec::new();
+        for elt in Self::get_response(&res)? {
+            let mut league: Value = elt["league"].clone();
+            let current_season: Option<&Value> = elt["seasons"]
+                .as_array()
+                .and_then(|seasons| {
+                    seasons
+                        .iter()
+                        .find(|season| season["current"].as_bool() == Some(true))
+                })
+                .map(|season| &season["year"]);
+            if let (Some(league), Some(current_season)) = (league.as_object_mut(), current_season) {
+                league.insert("currentSeason".into(), current_season.clone());
+            }
+            leagues.push(serde_json::from_value(league)?);
+        }
+        Ok(leagues)
+    }
+
+    async fn get_fixtures(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        league: Option<LeagueSeason>,
+    ) -> Result<Vec<game::Model>, CliError> {
+        match league {
+            Some(league) => {
+                let res = self
+                    .call_endpoint(format!(
+                        "fixtures?league={}&season={}&from={}&to={}",
+                        league.league_id, league.season, from, to
+                    ))
+                    .await?;
+                Self::get_models(&res)
+            }
+            // The ranges can only be fetched for a league.
+            None => {
+                let mut fixtures: Vec<game::Model> = Vec::new();
+                for date in super::get_days(from, to) {
+                    let res = self
+                        .call_endpoint(format!("fixtures?date={}", date))
+                        .await?;
+                    fixtures.extend(Self::get_models::<game::Model>(&res)?);
+                }
+                Ok(fixtures)
+            }
+        }
+    }
+
+    async fn get_odds(
+        &self,
+        date: NaiveDate,
+        league: Option<LeagueSeason>,
+        bookmaker_id: Option<u32>,
+        page: u64,
+    ) -> Result<OddsPage, CliError> {
+        let mut endpoint: String = match league {
+            Some(league) => format!(
+                "odds?league={}&season={}&date={}&page={}",
+                league.league_id, league.season, date, page
+            ),
+            None => format!("odds?date={}&page={}", date, page),
+        };
+        if let Some(bookmaker_id) = bookmaker_id {
+            endpoint.push_str(&format!("&bookmaker={}", bookmaker_id));
+        }
+        let res = self.call_endpoint(endpoint).await?;
+        Ok(OddsPage {
+            odds: Self::get_models::<odd::Model>(&res)?,
+            total_pages: res["paging"]["total"].as_u64().unwrap_or(page),
+        })
+    }
+
+    async fn get_fixture_odds(
+        &self,
+        _date: NaiveDate,
+        fixture_id: u32,
+    ) -> Result<Vec<odd::Model>, CliError> {
+        let res = self
+            .call_endpoint(format!("odds?fixture={}", fixture_id))
+            .await?;
+        Self::get_models(&res)
+    }
+
+    async fn get_bookmakers(&self) -> Result<Vec<bookmaker::Model>, CliError> {
+        let res = self.call_endpoint("odds/bookmakers".into()).await?;
+        Self::get_models(&res)
+    }
+
+    fn estimate_calls(&self, fetch: PlannedFetch) -> u64 {
+        if let Some(Recording::Replay(_)) = &self.recording {
+            return 0;
+        }
+        match fetch {
+            PlannedFetch::Leagues | PlannedFetch::Bookmakers => 1,
+            // A range is fetched with one call per league, or one call per day
+            // for every league.
+            PlannedFetch::Fixtures { days, leagues } => leagues.unwrap_or(days),
+            // The odds are paginated, at least one page is fetched per day and
+            // league, and one more call per upcoming fixture the main
+            // bookmaker has no odds for.
+            PlannedFetch::Odds { days, leagues } => days * leagues.unwrap_or(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Record a response of the bookmakers endpoint within the directory.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the recordings.
+    /// - rank : the rank of the call among the calls of the endpoint.
+    /// - status : the HTTP status of the response.
+    /// - body : the body of the response.
+    async fn record_bookmakers(dir: &Path, rank: usize, status: u16, body: Value) {
+        let recorded = RecordedResponse {
+            endpoint: "odds/bookmakers".into(),
+            status,
+            headers: BTreeMap::new(),
+            body,
+        };
+        ApiFootball::record(dir, &recorded, rank).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn replay_follows_the_recorded_calls_and_status() {
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("ffb_cli_replay_{}", std::process::id()));
+        record_bookmakers(
+            &dir,
+            1,
+            200,
+            serde_json::json!({"response": [{"id": 1, "name": "Unibet"}]}),
+        )
+        .await;
+        record_bookmakers(
+            &dir,
+            2,
+            429,
+            serde_json::json!({"message": "Too many requests"}),
+        )
+        .await;
+        let provider = ApiFootball::new(Some(Recording::Replay(dir.clone())));
+        let bookmakers: Vec<bookmaker::Model> = provider.get_bookmakers().await.unwrap();
+        assert_eq!(bookmakers.len(), 1);
+        assert_eq!(bookmakers[0].name, "Unibet");
+        assert!(matches!(
+            provider.get_bookmakers().await,
+            Err(CliError::RequestError(_))
+        ));
+        // No third call has been recorded.
+        assert!(matches!(
+            provider.get_bookmakers().await,
+            Err(CliError::InputOutput(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
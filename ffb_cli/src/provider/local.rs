@@ -0,0 +1,184 @@
+//! A provider reading the football data from local JSON files rather than
+//! from a remote API.
+//!
+//! It is meant to run the application without any API token, on a fixed set
+//! of data such as the one of a development or test environment.
+
+use super::{FootballDataProvider, LeagueSeason, OddsPage};
+use crate::error::CliError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use ffb_structs::{bookmaker, game, league, odd};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// The provider reading a directory of local JSON files.
+///
+/// The directory is given by the `LOCAL_PROVIDER_PATH` environment variable,
+/// and is organized as such :
+///
+/// - `leagues.json` : the leagues.
+/// - `bookmakers.json` : the bookmakers.
+/// - `fixtures/YYYY-MM-DD.json` : the fixtures of a day.
+/// - `odds/YYYY-MM-DD.json` : the odds of the fixtures of a day, on a single
+///   page.
+///
+/// Each file contains an array of models, in the shape they are stored within
+/// the application. A day without any file has no fixture or odds.
+#[derive(Debug)]
+pub(crate) struct LocalFiles {
+    /// The directory the files are read from.
+    path: PathBuf,
+}
+
+impl LocalFiles {
+    /// Create the provider from the `LOCAL_PROVIDER_PATH` environment
+    /// variable.
+    pub(crate) fn from_env() -> Result<Self, CliError> {
+        Ok(Self::new(std::env::var("LOCAL_PROVIDER_PATH")?))
+    }
+
+    /// Create the provider.
+    ///
+    /// # Arguments
+    ///
+    /// - path : the directory the files are read from.
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Read the array of values of a file.
+    ///
+    /// # Arguments
+    ///
+    /// - file : the path of the file, relative to the provider's directory.
+    async fn read_values(&self, file: &Path) -> Result<Vec<Value>, CliError> {
+        let path: PathBuf = self.path.join(file);
+        debug!("Local file read : {}", path.display());
+        let content: String = async_std::fs::read_to_string(&path).await?;
+        match serde_json::from_str::<Value>(&content)? {
+            Value::Array(models) => Ok(models),
+            _ => Err(CliError::SerdeErr(format!(
+                "The file {} doesn't contain an array",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Read the array of models of a file.
+    ///
+    /// # Arguments
+    ///
+    /// - file : the path of the file, relative to the provider's directory.
+    async fn read<T: DeserializeOwned>(&self, file: &Path) -> Result<Vec<T>, CliError> {
+        Self::into_models(self.read_values(file).await?)
+    }
+
+    /// Convert the values read into models.
+    ///
+    /// # Arguments
+    ///
+    /// - values : the values read.
+    fn into_models<T: DeserializeOwned>(values: Vec<Value>) -> Result<Vec<T>, CliError> {
+        values
+            .into_iter()
+            .map(|value| Ok(serde_json::from_value(value)?))
+            .collect()
+    }
+
+    /// Read the array of values of a day's file, if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the file, relative to the provider's
+    ///   directory.
+    /// - date : the day.
+    async fn read_day_values(&self, dir: &str, date: NaiveDate) -> Result<Vec<Value>, CliError> {
+        let file: PathBuf = Path::new(dir).join(format!("{}.json", date));
+        if !self.path.join(&file).exists() {
+            warn!("No local file {} found, the day is empty", file.display());
+            return Ok(Vec::new());
+        }
+        self.read_values(&file).await
+    }
+
+    /// Read the array of models of a day's file, if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// - dir : the directory of the file, relative to the provider's
+    ///   directory.
+    /// - date : the day.
+    /// - league : the league to restrict the models to, if any.
+    async fn read_day<T: DeserializeOwned>(
+        &self,
+        dir: &str,
+        date: NaiveDate,
+        league: Option<LeagueSeason>,
+    ) -> Result<Vec<T>, CliError> {
+        // The odds don't keep their league once stored, the values are
+        // filtered before being converted.
+        let values: Vec<Value> = self.read_day_values(dir, date).await?;
+        Self::into_models(match league {
+            Some(league) => values
+                .into_iter()
+                .filter(|value| value["league"]["id"].as_u64() == Some(league.league_id.into()))
+                .collect(),
+            None => values,
+        })
+    }
+}
+
+#[async_trait]
+impl FootballDataProvider for LocalFiles {
+    async fn get_leagues(&self) -> Result<Vec<league::Model>, CliError> {
+        self.read(Path::new("leagues.json")).await
+    }
+
+    async fn get_fixtures(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        league: Option<LeagueSeason>,
+    ) -> Result<Vec<game::Model>, CliError> {
+        let mut fixtures: Vec<game::Model> = Vec::new();
+        for date in super::get_days(from, to) {
+            fixtures.extend(self.read_day("fixtures", date, league).await?);
+        }
+        Ok(fixtures)
+    }
+
+    async fn get_odds(
+        &self,
+        date: NaiveDate,
+        league: Option<LeagueSeason>,
+        _bookmaker_id: Option<u32>,
+        _page: u64,
+    ) -> Result<OddsPage, CliError> {
+        // The files aren't restricted to a bookmaker, the odds of all the
+        // bookmakers are kept.
+        Ok(OddsPage {
+            odds: self.read_day::<odd::Model>("odds", date, league).await?,
+            total_pages: 1,
+        })
+    }
+
+    async fn get_fixture_odds(
+        &self,
+        date: NaiveDate,
+        fixture_id: u32,
+    ) -> Result<Vec<odd::Model>, CliError> {
+        let values: Vec<Value> = self.read_day_values("odds", date).await?;
+        Self::into_models(
+            values
+                .into_iter()
+                .filter(|value| value["fixture"]["id"].as_u64() == Some(fixture_id.into()))
+                .collect(),
+        )
+    }
+
+    async fn get_bookmakers(&self) -> Result<Vec<bookmaker::Model>, CliError> {
+        self.read(Path::new("bookmakers.json")).await
+    }
+}
@@ -0,0 +1,183 @@
+//! The providers of the football data used by the application.
+//!
+//! A provider returns the leagues, fixtures, odds and bookmakers as the models
+//! stored within the application, whatever the shape of its own source is.
+//! This way the source of the data can be switched without impacting the
+//! stored models, and a source returning data of another shape fails within
+//! its provider rather than while the models are stored.
+//!
+//! The provider used is picked with the `DATA_PROVIDER` environment variable,
+//! which can be either `api-football`, the default one, or `local`.
+//!
+//! The responses of the api-football provider can be recorded within a
+//! directory, and replayed later on to reproduce a run.
+
+use crate::error::CliError;
+use api_football::Recording;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use ffb_structs::{bookmaker, game, league, odd};
+
+/// The api-football provider, through RapidAPI.
+pub(crate) mod api_football;
+/// The provider reading a directory of local JSON files.
+pub(crate) mod local;
+
+/// A page of odds.
+///
+/// The odds are usually paginated by the providers, the number of pages being
+/// unknown before the first page has been fetched.
+#[derive(Debug)]
+pub(crate) struct OddsPage {
+    /// The odds of the page.
+    pub(crate) odds: Vec<odd::Model>,
+    /// The total number of pages.
+    pub(crate) total_pages: u64,
+}
+
+/// The season of a league, to restrict a fetch to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LeagueSeason {
+    /// The id of the league.
+    pub(crate) league_id: u32,
+    /// The year the season has started.
+    pub(crate) season: u32,
+}
+
+/// A fetch planned, whose cost can be estimated before it is run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PlannedFetch {
+    /// Fetch the leagues.
+    Leagues,
+    /// Fetch the bookmakers.
+    Bookmakers,
+    /// Fetch the fixtures of a number of days, for a number of leagues or
+    /// every league if none.
+    Fixtures { days: u64, leagues: Option<u64> },
+    /// Fetch the odds of a number of days, for a number of leagues or every
+    /// league if none.
+    Odds { days: u64, leagues: Option<u64> },
+}
+
+/// A source of football data.
+#[async_trait]
+pub(crate) trait FootballDataProvider {
+    /// Get the leagues, along with their current season.
+    async fn get_leagues(&self) -> Result<Vec<league::Model>, CliError>;
+
+    /// Get the fixtures played between two days.
+    ///
+    /// # Arguments
+    ///
+    /// - from : the first day of the fixtures.
+    /// - to : the last day of the fixtures.
+    /// - league : the league to restrict the fixtures to, if any.
+    async fn get_fixtures(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        league: Option<LeagueSeason>,
+    ) -> Result<Vec<game::Model>, CliError>;
+
+    /// Get a page of the odds of the fixtures of a day.
+    ///
+    /// # Arguments
+    ///
+    /// - date : the day of the fixtures.
+    /// - league : the league to restrict the odds to, if any.
+    /// - bookmaker_id : the bookmaker to restrict the odds to, if any.
+    /// - page : the page to get, starting from 1.
+    async fn get_odds(
+        &self,
+        date: NaiveDate,
+        league: Option<LeagueSeason>,
+        bookmaker_id: Option<u32>,
+        page: u64,
+    ) -> Result<OddsPage, CliError>;
+
+    /// Get the odds of all the bookmakers for a fixture.
+    ///
+    /// # Arguments
+    ///
+    /// - date : the day of the fixture.
+    /// - fixture_id : the id of the fixture.
+    async fn get_fixture_odds(
+        &self,
+        date: NaiveDate,
+        fixture_id: u32,
+    ) -> Result<Vec<odd::Model>, CliError>;
+
+    /// Get the bookmakers.
+    async fn get_bookmakers(&self) -> Result<Vec<bookmaker::Model>, CliError>;
+
+    /// Estimate the minimum number of calls to the API a fetch costs.
+    ///
+    /// The providers that don't call a metered API cost nothing.
+    ///
+    /// # Arguments
+    ///
+    /// - fetch : the fetch planned.
+    fn estimate_calls(&self, _fetch: PlannedFetch) -> u64 {
+        0
+    }
+}
+
+/// Get the days between two days, both included.
+///
+/// # Arguments
+///
+/// - from : the first day.
+/// - to : the last day.
+pub(crate) fn get_days(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let mut days: Vec<NaiveDate> = Vec::new();
+    let mut day: NaiveDate = from;
+    while day <= to {
+        days.push(day);
+        day = day.succ();
+    }
+    days
+}
+
+/// Get the provider set by the `DATA_PROVIDER` environment variable.
+///
+/// # Arguments
+///
+/// - recording : whether to record or replay the responses of the API, only
+///   used by the api-football provider.
+pub(crate) fn from_env(
+    recording: Option<Recording>,
+) -> Result<Box<dyn FootballDataProvider + Send + Sync>, CliError> {
+    match std::env::var("DATA_PROVIDER").as_deref() {
+        Ok("api-football") | Err(std::env::VarError::NotPresent) => {
+            Ok(Box::new(api_football::ApiFootball::new(recording)))
+        }
+        Ok("local") => {
+            if recording.is_some() {
+                warn!("The responses of the local provider can't be recorded nor replayed");
+            }
+            Ok(Box::new(local::LocalFiles::from_env()?))
+        }
+        Ok(other) => Err(CliError::VarError(format!(
+            "The data provider {} is unknown, it has to be either api-football or local",
+            other
+        ))),
+        Err(err) => Err(err.clone().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_include_both_ends() {
+        let from = NaiveDate::from_ymd(2022, 2, 27);
+        let to = NaiveDate::from_ymd(2022, 3, 1);
+        assert_eq!(
+            get_days(from, to),
+            vec![from, NaiveDate::from_ymd(2022, 2, 28), to]
+        );
+        assert_eq!(get_days(from, from), vec![from]);
+        assert!(get_days(to, from).is_empty());
+    }
+}
@@ -2,7 +2,10 @@ use crate::error::ApplicationError;
 use crate::uri_builder::{MessageType, UriBuilder};
 use actix_web::http::Uri;
 use actix_web::{post, HttpRequest, HttpResponse};
-use ffb_structs::{bookmaker, season};
+use ffb_structs::{
+    bookmaker, scoring_rule::ScoringRule, season, season::SettlementPolicy,
+    tie_break_policy::TieBreakPolicy,
+};
 
 #[derive(serde::Deserialize, validator::Validate)]
 pub struct MainBookmakerUpdate {
@@ -37,6 +40,11 @@ pub async fn admin_bookmakers(
 #[derive(serde::Deserialize, validator::Validate)]
 pub struct AddSeason {
     name: String,
+    settlement_policy: SettlementPolicy,
+    #[validate(range(min = 1))]
+    starting_balance: u32,
+    scoring_rule: ScoringRule,
+    tie_break_policy: TieBreakPolicy,
 }
 
 #[post("/admin/season/add")]
@@ -50,7 +58,14 @@ pub async fn admin_season_add(
         .ok_or(ApplicationError::InternalError)?
         .to_str()?;
     let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
-    let result = season::Entity::add_new(&add_season.name).await?;
+    let result = season::Entity::add_new(
+        &add_season.name,
+        add_season.settlement_policy,
+        add_season.starting_balance,
+        add_season.scoring_rule,
+        add_season.tie_break_policy,
+    )
+    .await?;
     if result.into() {
         uri_builder.append_msg(
             MessageType::Info,
@@ -0,0 +1,97 @@
+use crate::error::ApplicationError;
+use crate::uri_builder::{MessageType, UriBuilder};
+use actix_web::http::Uri;
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use ffb_auth::JwtUser;
+use ffb_structs::challenge;
+use ffb_structs::error::ApplicationError as StructApplicationError;
+
+/// Challenges another user.
+///
+/// The form contains the login of the opponent and a fixture_id entry for
+/// each game the challenge stands on.
+#[post("/challenges/add")]
+pub async fn create_challenge(
+    req: HttpRequest,
+    challenge_form: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    let mut opponent: Option<String> = None;
+    let mut fixture_ids: Vec<u32> = Vec::new();
+    for (key, value) in challenge_form.into_inner() {
+        match key.as_str() {
+            "opponent" => opponent = Some(value.trim().into()),
+            "fixture_id" => {
+                fixture_ids.push(value.parse().map_err(|_| ApplicationError::BadRequest)?)
+            }
+            _ => return Err(ApplicationError::BadRequest),
+        }
+    }
+    let opponent: String = opponent.ok_or(ApplicationError::BadRequest)?;
+    match challenge::Entity::create(jwt_user.id, &opponent, fixture_ids).await {
+        Ok(_) => {
+            uri_builder.append_msg(
+                MessageType::Info,
+                &format!("{} has been challenged", opponent),
+            );
+        }
+        Err(
+            err @ (StructApplicationError::InvalidOpponent(_)
+            | StructApplicationError::EmptyChallenge
+            | StructApplicationError::GameNotFound(_)
+            | StructApplicationError::GameNotABet(_)
+            | StructApplicationError::SeasonClosed(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+#[derive(serde::Deserialize, validator::Validate)]
+pub struct AnswerChallengeForm {
+    id: u32,
+    accept: bool,
+}
+
+#[post("/challenges/answer")]
+pub async fn answer_challenge(
+    req: HttpRequest,
+    answer_form: actix_web_validator::Form<AnswerChallengeForm>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    match challenge::Entity::answer(jwt_user.id, answer_form.id, answer_form.accept).await {
+        Ok(_) if answer_form.accept => {
+            uri_builder.append_msg(MessageType::Info, "The challenge has been accepted");
+        }
+        Ok(_) => {
+            uri_builder.append_msg(MessageType::Info, "The challenge has been declined");
+        }
+        Err(
+            err @ (StructApplicationError::ChallengeNotFound(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
@@ -1,8 +1,16 @@
 use crate::error::ApplicationError;
 use crate::uri_builder::{MessageType, UriBuilder};
 use actix_web::http::Uri;
-use actix_web::{post, HttpRequest, HttpResponse};
-use ffb_structs::{bet, bet::GameResult, game};
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use ffb_auth::JwtUser;
+use ffb_structs::error::ApplicationError as StructApplicationError;
+use ffb_structs::{
+    bet, bet::GameResult, bet_slip, game, market_bet, market_bet::MarketPick, score_bet,
+};
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(serde::Deserialize, validator::Validate)]
 pub struct ChangeGameGameResultStatus {
@@ -40,11 +48,12 @@ pub async fn update_game_status(
 
 #[derive(serde::Deserialize, validator::Validate)]
 pub struct GameResultOnGameForm {
-    user_id: u32,
     fixture_id: u32,
-    season_id: u32,
     bet: GameResult,
-    stake: f32,
+    #[validate(range(min = 1))]
+    amount: u32,
+    #[serde(default)]
+    joker: bool,
 }
 
 #[post("/games/bet")]
@@ -52,21 +61,196 @@ pub async fn bet_on_game(
     req: HttpRequest,
     bet_form: actix_web_validator::Form<GameResultOnGameForm>,
 ) -> Result<HttpResponse, ApplicationError> {
-    bet::Entity::upsert_bet(
-        bet_form.user_id,
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    match bet::Entity::upsert_bet(
+        jwt_user.id,
         bet_form.fixture_id,
-        bet_form.season_id,
         bet_form.bet,
-        bet_form.stake,
+        bet_form.amount,
+        bet_form.joker,
     )
-    .await?;
+    .await
+    {
+        Ok(_) => {
+            uri_builder.append_msg(MessageType::Info, "Your bet has been successfully saved");
+        }
+        Err(
+            err @ (StructApplicationError::GameNotFound(_)
+            | StructApplicationError::GameNotABet(_)
+            | StructApplicationError::NoOddsForGame(_)
+            | StructApplicationError::SeasonClosed(_)
+            | StructApplicationError::AlreadyBet(_)
+            | StructApplicationError::InsufficientBalance(_)
+            | StructApplicationError::JokerAlreadyUsed(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+#[derive(serde::Deserialize, validator::Validate)]
+pub struct ScoreOnGameForm {
+    fixture_id: u32,
+    #[validate(range(max = 20))]
+    home_goals: u8,
+    #[validate(range(max = 20))]
+    away_goals: u8,
+}
+
+#[post("/games/bet/score")]
+pub async fn bet_score_on_game(
+    req: HttpRequest,
+    score_form: actix_web_validator::Form<ScoreOnGameForm>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
     let referer: &str = req
         .headers()
         .get("referer")
         .ok_or(ApplicationError::InternalError)?
         .to_str()?;
     let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
-    uri_builder.append_msg(MessageType::Info, "Your bet has been successfully saved");
+    match score_bet::Entity::upsert_score_bet(
+        jwt_user.id,
+        score_form.fixture_id,
+        score_form.home_goals,
+        score_form.away_goals,
+    )
+    .await
+    {
+        Ok(_) => {
+            uri_builder.append_msg(
+                MessageType::Info,
+                "Your score prediction has been successfully saved",
+            );
+        }
+        Err(
+            err @ (StructApplicationError::GameNotFound(_)
+            | StructApplicationError::GameNotABet(_)
+            | StructApplicationError::SeasonClosed(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+#[derive(serde::Deserialize, validator::Validate)]
+pub struct MarketPickOnGameForm {
+    fixture_id: u32,
+    pick: MarketPick,
+    #[validate(range(min = 1))]
+    amount: u32,
+}
+
+#[post("/games/bet/market")]
+pub async fn bet_market_on_game(
+    req: HttpRequest,
+    market_form: actix_web_validator::Form<MarketPickOnGameForm>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    match market_bet::Entity::upsert_market_bet(
+        jwt_user.id,
+        market_form.fixture_id,
+        market_form.pick,
+        market_form.amount,
+    )
+    .await
+    {
+        Ok(_) => {
+            uri_builder.append_msg(MessageType::Info, "Your bet has been successfully saved");
+        }
+        Err(
+            err @ (StructApplicationError::GameNotFound(_)
+            | StructApplicationError::GameNotABet(_)
+            | StructApplicationError::NoOddsForGame(_)
+            | StructApplicationError::SeasonClosed(_)
+            | StructApplicationError::InsufficientBalance(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+/// Places an accumulator.
+///
+/// The form maps the fixture ids to the user's pick, the games that haven't
+/// been picked aren't sent, besides the amount put on the accumulator.
+#[post("/accumulators/add")]
+pub async fn place_accumulator(
+    req: HttpRequest,
+    picks_form: web::Form<HashMap<String, String>>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    let mut picks_form: HashMap<String, String> = picks_form.into_inner();
+    let amount: u32 = picks_form
+        .remove("amount")
+        .and_then(|amount| amount.parse::<u32>().ok())
+        .filter(|amount| 0 < *amount)
+        .ok_or(ApplicationError::BadRequest)?;
+    let picks: Vec<(u32, GameResult)> = picks_form
+        .into_iter()
+        .map(|(fixture_id, game_result)| {
+            let game_result: StrDeserializer<ValueError> = game_result.as_str().into_deserializer();
+            Ok((
+                fixture_id.parse::<u32>()?,
+                GameResult::deserialize(game_result)?,
+            ))
+        })
+        .collect::<Result<_, Box<dyn std::error::Error>>>()
+        .map_err(|_| ApplicationError::BadRequest)?;
+    match bet_slip::Entity::place(jwt_user.id, picks, amount).await {
+        Ok(_) => {
+            uri_builder.append_msg(
+                MessageType::Info,
+                "Your accumulator has been successfully placed",
+            );
+        }
+        Err(
+            err @ (StructApplicationError::GameNotFound(_)
+            | StructApplicationError::GameNotABet(_)
+            | StructApplicationError::NoOddsForGame(_)
+            | StructApplicationError::SeasonClosed(_)
+            | StructApplicationError::NotEnoughLegs(_)
+            | StructApplicationError::AlreadyBet(_)
+            | StructApplicationError::InsufficientBalance(_)
+            | StructApplicationError::FormOutdated),
+        ) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
     Ok(HttpResponse::Found()
         .append_header(("Location", uri_builder.build()))
         .finish())
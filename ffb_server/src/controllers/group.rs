@@ -0,0 +1,114 @@
+use crate::error::ApplicationError;
+use crate::uri_builder::{MessageType, UriBuilder};
+use actix_web::http::Uri;
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use ffb_auth::JwtUser;
+use ffb_structs::error::ApplicationError as StructApplicationError;
+use ffb_structs::group;
+
+/// Creates a new group.
+///
+/// The form contains the name of the group and a league_id entry for each
+/// competition the group is restricted to.
+#[post("/groups/add")]
+pub async fn create_group(
+    req: HttpRequest,
+    group_form: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    let mut name: Option<String> = None;
+    let mut league_ids: Vec<u32> = Vec::new();
+    for (key, value) in group_form.into_inner() {
+        match key.as_str() {
+            "name" => name = Some(value.trim().into()),
+            "league_id" => {
+                league_ids.push(value.parse().map_err(|_| ApplicationError::BadRequest)?)
+            }
+            _ => return Err(ApplicationError::BadRequest),
+        }
+    }
+    let name: String = name
+        .filter(|name| (3..=32).contains(&name.chars().count()))
+        .ok_or(ApplicationError::BadRequest)?;
+    let group: group::Model = group::Entity::create(jwt_user.id, &name, league_ids).await?;
+    uri_builder.append_msg(
+        MessageType::Info,
+        &format!(
+            "The group {} has been created, share the code {} to invite other players",
+            group.name, group.invite_code
+        ),
+    );
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+#[derive(serde::Deserialize, validator::Validate)]
+pub struct JoinGroupForm {
+    #[validate(length(equal = 8))]
+    invite_code: String,
+}
+
+#[post("/groups/join")]
+pub async fn join_group(
+    req: HttpRequest,
+    join_form: actix_web_validator::Form<JoinGroupForm>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    match group::Entity::join(jwt_user.id, &join_form.invite_code).await {
+        Ok(group) => {
+            uri_builder.append_msg(
+                MessageType::Info,
+                &format!("You have joined the group {}", group.name),
+            );
+        }
+        Err(err @ StructApplicationError::InvalidInviteCode(_)) => {
+            uri_builder.append_msg(MessageType::Error, &err.to_string());
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
+
+#[derive(serde::Deserialize, validator::Validate)]
+pub struct LeaveGroupForm {
+    id: u32,
+}
+
+#[post("/groups/leave")]
+pub async fn leave_group(
+    req: HttpRequest,
+    leave_form: actix_web_validator::Form<LeaveGroupForm>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req.clone())?;
+    let referer: &str = req
+        .headers()
+        .get("referer")
+        .ok_or(ApplicationError::InternalError)?
+        .to_str()?;
+    let mut uri_builder: UriBuilder = UriBuilder::from_existing_uri(referer.parse::<Uri>()?);
+    let result: bool = group::Entity::leave(jwt_user.id, leave_form.id)
+        .await?
+        .into();
+    match result {
+        true => uri_builder.append_msg(MessageType::Info, "You have left the group"),
+        false => uri_builder.append_msg(MessageType::Error, "You aren't a member of this group"),
+    };
+    Ok(HttpResponse::Found()
+        .append_header(("Location", uri_builder.build()))
+        .finish())
+}
@@ -1,6 +1,8 @@
 pub mod admin;
 pub mod auth;
+pub mod challenge;
 pub mod club;
 pub mod cookies;
 pub mod game;
+pub mod group;
 pub mod user;
@@ -20,7 +20,11 @@ use crate::controllers::admin::{
 use crate::controllers::auth::{login, logout, register_user};
 use crate::controllers::club::update_club_status;
 use crate::controllers::cookies::cookies_approved;
-use crate::controllers::game::{bet_on_game, update_game_status};
+use crate::controllers::game::{
+    bet_market_on_game, bet_on_game, bet_score_on_game, place_accumulator, update_game_status,
+};
+use crate::controllers::challenge::{answer_challenge, create_challenge};
+use crate::controllers::group::{create_group, join_group, leave_group};
 use crate::controllers::user::{
     user_activation, user_change_leagues, user_deletion, user_modification, user_search,
     user_self_modification,
@@ -31,8 +35,11 @@ use crate::middleware::ddos_limiter::DDosLimiter;
 use crate::middleware::protect_assets::AssetsProtector;
 use crate::middleware::role_checker::RoleChecker;
 use crate::pages::admin::{admin_bookmakers, admin_dashboard, admin_seasons};
+use crate::pages::bets::{accumulators, my_bets, my_bets_api};
 use crate::pages::game::games;
-use crate::pages::leaderboard::leaderboard;
+use crate::pages::challenge::challenges;
+use crate::pages::group::groups;
+use crate::pages::leaderboard::{leaderboard, rank_history};
 use crate::pages::unauth::{cookies, index, signup};
 use crate::pages::user::{user_club, user_leagues, user_profile};
 use actix_files as fs;
@@ -127,7 +134,21 @@ async fn main() -> std::io::Result<()> {
                             .service(admin_season_set_main)
                             .service(admin_season_close)
                             .service(leaderboard)
+                            .service(rank_history)
                             .service(bet_on_game)
+                            .service(bet_score_on_game)
+                            .service(bet_market_on_game)
+                            .service(my_bets)
+                            .service(my_bets_api)
+                            .service(accumulators)
+                            .service(place_accumulator)
+                            .service(groups)
+                            .service(create_group)
+                            .service(join_group)
+                            .service(leave_group)
+                            .service(challenges)
+                            .service(create_challenge)
+                            .service(answer_challenge)
                     ),
             )
     })
@@ -0,0 +1,158 @@
+use ffb_auth::JwtUser;
+
+use crate::pages::ContextQuery;
+use askama::Template;
+
+use crate::error::ApplicationError;
+use crate::ApplicationData;
+use actix_web::web;
+use actix_web::{get, HttpRequest, HttpResponse};
+use ffb_structs::bet_history::{
+    BetLeague, Entity as BetHistoryEntity, EntityBuilder as BetHistoryBuilder, Model as BetHistory,
+};
+use ffb_structs::bet_slip::{Entity as BetSlipEntity, Model as BetSlip};
+use ffb_structs::game::{Entity as GameEntity, Model as Game};
+use ffb_structs::season::{EntityBuilder as SeasonBuilder, Model as Season};
+
+#[derive(Template)]
+#[template(path = "bets/my_bets.html")]
+struct MyBets {
+    title: String,
+    user: Option<JwtUser>,
+    error: Option<String>,
+    info: Option<String>,
+    data: BetHistory,
+    seasons: Vec<Season>,
+    leagues: Vec<BetLeague>,
+    selected_season: Option<u32>,
+    selected_league: Option<u32>,
+    selected_status: Option<String>,
+    filters_query: String,
+    page: u32,
+    per_page: u32,
+    app_data: web::Data<ApplicationData>,
+}
+
+impl MyBets {
+    fn is_season_selected(&self, id: &u32) -> bool {
+        self.selected_season == Some(*id)
+    }
+
+    fn is_league_selected(&self, id: &u32) -> bool {
+        self.selected_league == Some(*id)
+    }
+
+    fn is_status_selected(&self, status: &str) -> bool {
+        self.selected_status.as_deref() == Some(status)
+    }
+
+    fn has_next_page(&self) -> bool {
+        self.data.entries.len() as u32 == self.per_page
+    }
+}
+
+#[derive(Template)]
+#[template(path = "bets/accumulators.html")]
+struct Accumulators {
+    title: String,
+    user: Option<JwtUser>,
+    error: Option<String>,
+    info: Option<String>,
+    slips: Vec<BetSlip>,
+    open_games: Vec<Game>,
+    min_legs: usize,
+    app_data: web::Data<ApplicationData>,
+}
+
+/// Builds the bet history lookup from the query.
+fn bet_history_builder(user_id: u32, context_query: &ContextQuery) -> BetHistoryBuilder {
+    let mut builder = BetHistoryBuilder::build(user_id);
+    builder
+        .season_id(context_query.season)
+        .league_id(context_query.league)
+        .status(context_query.status)
+        .page(context_query.page.unwrap_or(0))
+        .per_page(context_query.per_page.unwrap_or(10));
+    builder
+}
+
+#[get("/mybets")]
+pub async fn my_bets(
+    req: HttpRequest,
+    context_query: actix_web_validator::Query<ContextQuery>,
+    app_data: web::Data<ApplicationData>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let page: u32 = context_query.page.unwrap_or(0);
+    let per_page: u32 = context_query.per_page.unwrap_or(10);
+    let data: BetHistory = bet_history_builder(jwt_user.id, &context_query)
+        .finish()
+        .await?;
+    let seasons: Vec<Season> = SeasonBuilder::build().finish().await?;
+    let leagues: Vec<BetLeague> = BetHistoryEntity::get_bet_leagues(jwt_user.id).await?;
+    let mut filters_query: String = format!("per_page={}", per_page);
+    if let Some(season) = context_query.season {
+        filters_query.push_str(&format!("&season={}", season));
+    }
+    if let Some(league) = context_query.league {
+        filters_query.push_str(&format!("&league={}", league));
+    }
+    if let Some(status) = context_query.status {
+        filters_query.push_str(&format!("&status={}", status));
+    }
+    let index = MyBets {
+        title: app_data
+            .translate("M50001_TITLE", &jwt_user.locale_id)?
+            .into(),
+        user: Some(jwt_user),
+        error: context_query.error.clone(),
+        info: context_query.info.clone(),
+        data,
+        seasons,
+        leagues,
+        selected_season: context_query.season,
+        selected_league: context_query.league,
+        selected_status: context_query.status.map(|status| status.to_string()),
+        filters_query,
+        page,
+        per_page,
+        app_data,
+    };
+    Ok(HttpResponse::Ok().body(index.render()?))
+}
+
+#[get("/api/mybets")]
+pub async fn my_bets_api(
+    req: HttpRequest,
+    context_query: actix_web_validator::Query<ContextQuery>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let data: BetHistory = bet_history_builder(jwt_user.id, &context_query)
+        .finish()
+        .await?;
+    Ok(HttpResponse::Ok().json(data))
+}
+
+#[get("/accumulators")]
+pub async fn accumulators(
+    req: HttpRequest,
+    context_query: actix_web_validator::Query<ContextQuery>,
+    app_data: web::Data<ApplicationData>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let slips: Vec<BetSlip> = BetSlipEntity::get_user_slips(jwt_user.id).await?;
+    let open_games: Vec<Game> = GameEntity::get_open_bets().await?;
+    let index = Accumulators {
+        title: app_data
+            .translate("M50002_TITLE", &jwt_user.locale_id)?
+            .into(),
+        user: Some(jwt_user),
+        error: context_query.error.clone(),
+        info: context_query.info.clone(),
+        slips,
+        open_games,
+        min_legs: ffb_structs::bet_slip::MIN_LEGS,
+        app_data,
+    };
+    Ok(HttpResponse::Ok().body(index.render()?))
+}
@@ -0,0 +1,46 @@
+use ffb_auth::JwtUser;
+
+use crate::pages::ContextQuery;
+use askama::Template;
+
+use crate::error::ApplicationError;
+use crate::ApplicationData;
+use actix_web::web;
+use actix_web::{get, HttpRequest, HttpResponse};
+use ffb_structs::challenge::{Entity as ChallengeEntity, Model as Challenge};
+use ffb_structs::game::{Entity as GameEntity, Model as Game};
+
+#[derive(Template)]
+#[template(path = "bets/challenges.html")]
+struct Challenges {
+    title: String,
+    user: Option<JwtUser>,
+    error: Option<String>,
+    info: Option<String>,
+    challenges: Vec<Challenge>,
+    open_games: Vec<Game>,
+    app_data: web::Data<ApplicationData>,
+}
+
+#[get("/challenges")]
+pub async fn challenges(
+    req: HttpRequest,
+    context_query: web::Query<ContextQuery>,
+    app_data: web::Data<ApplicationData>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let challenges: Vec<Challenge> = ChallengeEntity::get_user_challenges(jwt_user.id).await?;
+    let open_games: Vec<Game> = GameEntity::get_open_bets().await?;
+    let index = Challenges {
+        title: app_data
+            .translate("M50003_TITLE", &jwt_user.locale_id)?
+            .into(),
+        user: Some(jwt_user),
+        error: context_query.error.clone(),
+        info: context_query.info.clone(),
+        challenges,
+        open_games,
+        app_data,
+    };
+    Ok(HttpResponse::Ok().body(index.render()?))
+}
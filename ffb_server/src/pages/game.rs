@@ -10,8 +10,10 @@ use actix_web::{get, web, HttpRequest, HttpResponse};
 use chrono::{DateTime, Utc};
 use ffb_structs::{
     game::Entity as GameEntity, game::EntityBuilder as GameEntityBuilder, game::Model as Game,
-    season, user,
+    group, season, season::EntityBuilder as SeasonBuilder, season::Model as Season,
+    season::SettlementPolicy, user,
 };
+use std::collections::HashSet;
 
 #[derive(Template)]
 #[template(path = "games/game_row.html")]
@@ -23,8 +25,20 @@ struct GamesRowTemplate {
     title: String,
     fetched_on: Option<String>,
     current_season_id: u32,
+    seasons: Vec<Season>,
     app_data: web::Data<ApplicationData>,
     user: Option<JwtUser>,
+    friend_ids: HashSet<u32>,
+}
+
+impl GamesRowTemplate {
+    /// Get the settlement policy of the given season.
+    fn get_settlement_policy(&self, season_id: &u32) -> Option<SettlementPolicy> {
+        self.seasons
+            .iter()
+            .find(|season| &season.id == season_id)
+            .map(|season| season.settlement_policy)
+    }
 }
 
 #[derive(Template)]
@@ -62,6 +76,8 @@ pub async fn games(
     let jwt_user: JwtUser = JwtUser::from_request(req)?;
     let now: DateTime<Utc> = Utc::now();
     let current_season_id: u32 = season::Entity::get_current_season_id().await?;
+    let seasons: Vec<Season> = SeasonBuilder::build().finish().await?;
+    let friend_ids: HashSet<u32> = group::Entity::get_group_mate_ids(jwt_user.id).await?;
     let mut builder: GameEntityBuilder = GameEntityBuilder::build();
     match context_query.all {
         Some(v) if v => {}
@@ -95,8 +111,10 @@ pub async fn games(
                     .translate("M10001_GAME_OF_DAY", &jwt_user.locale_id)?
                     .into(),
                 current_season_id,
+                seasons: seasons.clone(),
                 app_data: app_data.clone(),
                 user: Some(jwt_user.clone()),
+                friend_ids: friend_ids.clone(),
             }),
             true => None,
         };
@@ -135,6 +153,7 @@ pub async fn games(
             )?,
             now,
             current_season_id,
+            seasons: seasons.clone(),
             fetched_date: now_as_simple_date,
             title: app_data
                 .translate("M10001_TODAY_TITLE", &jwt_user.locale_id)?
@@ -142,6 +161,7 @@ pub async fn games(
             user_role: jwt_user.role,
             app_data: app_data.clone(),
             user: Some(jwt_user.clone()),
+            friend_ids: friend_ids.clone(),
         }),
         true => None,
     };
@@ -157,6 +177,7 @@ pub async fn games(
             now,
             fetched_date: yesterday_as_simple_date.clone(),
             current_season_id,
+            seasons: seasons.clone(),
             title: app_data
                 .translate("M10001_YESTERDAY_TITLE", &jwt_user.locale_id)?
                 .into(),
@@ -166,6 +187,7 @@ pub async fn games(
             user_role: jwt_user.role,
             app_data: app_data.clone(),
             user: Some(jwt_user.clone()),
+            friend_ids: friend_ids.clone(),
         }),
         _ => None,
     };
@@ -188,7 +210,9 @@ pub async fn games(
             user_role: jwt_user.role,
             app_data: app_data.clone(),
             current_season_id,
+            seasons,
             user: Some(jwt_user.clone()),
+            friend_ids,
         }),
         true => None,
     };
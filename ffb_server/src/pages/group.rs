@@ -0,0 +1,71 @@
+use ffb_auth::JwtUser;
+
+use crate::pages::ContextQuery;
+use askama::Template;
+
+use crate::error::ApplicationError;
+use crate::ApplicationData;
+use actix_web::web;
+use actix_web::{get, HttpRequest, HttpResponse};
+use ffb_structs::group::{Entity as GroupEntity, Model as Group};
+use ffb_structs::league::{EntityBuilder as LeagueBuilder, Model as League};
+use ffb_structs::user;
+
+#[derive(Template)]
+#[template(path = "groups.html")]
+struct Groups {
+    title: String,
+    user: Option<JwtUser>,
+    error: Option<String>,
+    info: Option<String>,
+    groups: Vec<Group>,
+    fav_leagues: Vec<League>,
+    leagues: Vec<League>,
+    app_data: web::Data<ApplicationData>,
+}
+
+impl Groups {
+    /// Get the name of a league the groups are restricted to.
+    fn get_league_name(&self, league_id: &u32) -> Option<&str> {
+        self.leagues
+            .iter()
+            .find(|league| &league.id == league_id)
+            .map(|league| league.name.as_str())
+    }
+}
+
+#[get("/groups")]
+pub async fn groups(
+    req: HttpRequest,
+    context_query: actix_web_validator::Query<ContextQuery>,
+    app_data: web::Data<ApplicationData>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let groups: Vec<Group> = GroupEntity::get_user_groups(jwt_user.id).await?;
+    let fav_leagues_id: Vec<u32> = user::Entity::get_favorite_leagues_id(jwt_user.id).await?;
+    let fav_leagues: Vec<League> = LeagueBuilder::build()
+        .ids(Some(fav_leagues_id))
+        .finish()
+        .await?;
+    let league_ids: Vec<u32> = groups
+        .iter()
+        .flat_map(|group| group.league_ids.clone())
+        .collect();
+    let leagues: Vec<League> = LeagueBuilder::build()
+        .ids(Some(league_ids))
+        .finish()
+        .await?;
+    let index = Groups {
+        title: app_data
+            .translate("M40002_TITLE", &jwt_user.locale_id)?
+            .into(),
+        user: Some(jwt_user),
+        error: context_query.error.clone(),
+        info: context_query.info.clone(),
+        groups,
+        fav_leagues,
+        leagues,
+        app_data,
+    };
+    Ok(HttpResponse::Ok().body(index.render()?))
+}
@@ -7,8 +7,26 @@ use crate::error::ApplicationError;
 use crate::ApplicationData;
 use actix_web::web;
 use actix_web::{get, HttpRequest, HttpResponse};
-use ffb_structs::scoreboard::{EntityBuilder as ScoreboardBuilder, Model as Scoreboard};
-use ffb_structs::season::{EntityBuilder as SeasonBuilder, Model as Season};
+use ffb_structs::bet_history::{BetLeague, Entity as BetHistoryEntity};
+use ffb_structs::group::{Entity as GroupEntity, Model as Group};
+use ffb_structs::scoreboard::{
+    EntityBuilder as ScoreboardBuilder, Model as Scoreboard, RankBy, TimeWindow,
+};
+use ffb_structs::scoreboard_snapshot::{
+    Entity as ScoreboardSnapshotEntity, Model as ScoreboardSnapshot,
+};
+use ffb_structs::season::{
+    Entity as SeasonEntity, EntityBuilder as SeasonBuilder, Model as Season,
+};
+use ffb_structs::user::Entity as UserEntity;
+use std::collections::HashMap;
+
+/// The width of the ranking history's chart.
+const CHART_WIDTH: u32 = 600;
+/// The height of the ranking history's chart.
+const CHART_HEIGHT: u32 = 240;
+/// The space left around the ranking history's chart.
+const CHART_PADDING: u32 = 10;
 
 #[derive(Template)]
 #[template(path = "leaderboard.html")]
@@ -18,10 +36,59 @@ struct Leaderboard {
     error: Option<String>,
     info: Option<String>,
     data: Scoreboard,
+    rank_by: RankBy,
+    group: Option<Group>,
+    time_window: Option<TimeWindow>,
+    league_id: Option<u32>,
+    leagues: Vec<BetLeague>,
     seasons: Vec<Season>,
+    previous_rankings: HashMap<u32, u32>,
     app_data: web::Data<ApplicationData>,
 }
 
+impl Leaderboard {
+    /// Get the query string of the displayed scoreboard's scope, to rank it
+    /// by another column.
+    fn get_scope_query(&self) -> String {
+        self.get_query(self.time_window, self.league_id)
+    }
+
+    /// Get the query string of the displayed scoreboard's season and group
+    /// with the given time window and competition.
+    fn get_query(&self, time_window: Option<TimeWindow>, league_id: Option<u32>) -> String {
+        let mut query: String = match &self.data.season {
+            Some(season) => format!("id={}", season.id),
+            None => "all=true".into(),
+        };
+        if let Some(group) = &self.group {
+            query.push_str(&format!("&group_id={}", group.id));
+        }
+        if let Some(time_window) = time_window {
+            query.push_str(&format!("&window={}", time_window));
+        }
+        if let Some(league_id) = league_id {
+            query.push_str(&format!("&league={}", league_id));
+        }
+        query
+    }
+
+    fn is_league_selected(&self, id: &u32) -> bool {
+        self.league_id == Some(*id)
+    }
+
+    fn is_window_selected(&self, time_window: &TimeWindow) -> bool {
+        self.time_window == Some(*time_window)
+    }
+
+    /// Get the number of places a user has gained since the previous
+    /// snapshot, if the user was ranked then.
+    fn get_rank_change(&self, user_id: &u32, ranking: &u64) -> Option<i64> {
+        self.previous_rankings
+            .get(user_id)
+            .map(|previous_ranking| i64::from(*previous_ranking) - *ranking as i64)
+    }
+}
+
 #[get("/leaderboard")]
 pub async fn leaderboard(
     req: HttpRequest,
@@ -35,7 +102,37 @@ pub async fn leaderboard(
     } else if let Some(all_time) = context_query.all {
         scoreboard_builder.all_time(all_time);
     }
+    // The group leaderboards are only visible to the group's members.
+    let group: Option<Group> = match context_query.group_id {
+        Some(group_id) => Some(
+            GroupEntity::find_by_id_for_user(group_id, jwt_user.id)
+                .await?
+                .ok_or(ApplicationError::NotFound)?,
+        ),
+        None => None,
+    };
+    scoreboard_builder.group_id(context_query.group_id);
+    scoreboard_builder.league_id(context_query.league);
+    scoreboard_builder.time_window(context_query.window);
+    let rank_by: RankBy = context_query.rank_by.unwrap_or_default();
+    scoreboard_builder.rank_by(rank_by);
+    scoreboard_builder.breakdowns(true);
     let seasons: Vec<Season> = SeasonBuilder::build().finish().await?;
+    let leagues: Vec<BetLeague> = BetHistoryEntity::get_all_bet_leagues().await?;
+    let data: Scoreboard = scoreboard_builder.finish().await?;
+    // The snapshots only keep the season's ranking by points, so the change
+    // can't be told for the other rankings.
+    let previous_rankings: HashMap<u32, u32> = match &data.season {
+        Some(season)
+            if rank_by == RankBy::Points
+                && context_query.league.is_none()
+                && context_query.window.is_none() =>
+        {
+            ScoreboardSnapshotEntity::get_previous_rankings(season.id, context_query.group_id)
+                .await?
+        }
+        _ => HashMap::new(),
+    };
     let index = Leaderboard {
         title: app_data
             .translate("M40001_TITLE", &jwt_user.locale_id)?
@@ -43,8 +140,113 @@ pub async fn leaderboard(
         user: Some(jwt_user),
         error: context_query.error.clone(),
         info: context_query.info.clone(),
-        data: scoreboard_builder.finish().await?,
+        data,
+        rank_by,
+        group,
+        time_window: context_query.window,
+        league_id: context_query.league,
+        leagues,
+        seasons,
+        previous_rankings,
+        app_data,
+    };
+    Ok(HttpResponse::Ok().body(index.render()?))
+}
+
+#[derive(Template)]
+#[template(path = "rank_history.html")]
+struct RankHistory {
+    title: String,
+    user: Option<JwtUser>,
+    error: Option<String>,
+    info: Option<String>,
+    user_id: u32,
+    user_name: String,
+    season: Season,
+    seasons: Vec<Season>,
+    history: Vec<ScoreboardSnapshot>,
+    app_data: web::Data<ApplicationData>,
+}
+
+impl RankHistory {
+    /// Get the position of each snapshot on the chart, the first ranking
+    /// being on top.
+    fn get_chart_coordinates(&self) -> Vec<(u32, u32, ScoreboardSnapshot)> {
+        let lowest_ranking: u32 = self
+            .history
+            .iter()
+            .map(|snapshot| snapshot.ranking)
+            .max()
+            .unwrap_or(1);
+        let width: u32 = CHART_WIDTH - 2 * CHART_PADDING;
+        let height: u32 = CHART_HEIGHT - 2 * CHART_PADDING;
+        let last: u32 = self.history.len().saturating_sub(1) as u32;
+        self.history
+            .iter()
+            .enumerate()
+            .map(|(i, snapshot)| {
+                let x: u32 = match last {
+                    0 => width / 2,
+                    last => i as u32 * width / last,
+                };
+                let y: u32 = match lowest_ranking {
+                    1 => height / 2,
+                    lowest_ranking => (snapshot.ranking - 1) * height / (lowest_ranking - 1),
+                };
+                (x + CHART_PADDING, y + CHART_PADDING, snapshot.clone())
+            })
+            .collect()
+    }
+
+    /// Get the points of the chart's line.
+    fn get_chart_points(&self) -> String {
+        self.get_chart_coordinates()
+            .iter()
+            .map(|(x, y, _)| format!("{},{}", x, y))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Get the view box of the chart.
+    fn get_chart_view_box(&self) -> String {
+        format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+    }
+}
+
+#[get("/leaderboard/history")]
+pub async fn rank_history(
+    req: HttpRequest,
+    context_query: web::Query<ContextQuery>,
+    app_data: web::Data<ApplicationData>,
+) -> Result<HttpResponse, ApplicationError> {
+    let jwt_user: JwtUser = JwtUser::from_request(req)?;
+    let user_id: u32 = context_query.id.unwrap_or(jwt_user.id);
+    let user_name: String = UserEntity::find_by_id(user_id)
+        .await?
+        .ok_or(ApplicationError::NotFound)?
+        .name;
+    let season_id: u32 = match context_query.season {
+        Some(season_id) => season_id,
+        None => SeasonEntity::get_current_season_id().await?,
+    };
+    let season: Season = SeasonEntity::find_by_id(season_id)
+        .await?
+        .ok_or(ApplicationError::NotFound)?;
+    let seasons: Vec<Season> = SeasonBuilder::build().finish().await?;
+    let history: Vec<ScoreboardSnapshot> =
+        ScoreboardSnapshotEntity::get_user_history(user_id, season_id).await?;
+    let index = RankHistory {
+        title: app_data
+            .translate("M40003_TITLE", &jwt_user.locale_id)?
+            .into(),
+        user: Some(jwt_user),
+        error: context_query.error.clone(),
+        info: context_query.info.clone(),
+        user_id,
+        user_name,
+        season,
         seasons,
+        history,
         app_data,
     };
     Ok(HttpResponse::Ok().body(index.render()?))
@@ -1,5 +1,11 @@
+use ffb_structs::bet_history_entry::BetStatus;
+use ffb_structs::scoreboard::{RankBy, TimeWindow};
+
 pub mod admin;
+pub mod bets;
+pub mod challenge;
 pub mod game;
+pub mod group;
 pub mod leaderboard;
 pub mod unauth;
 pub mod user;
@@ -19,9 +25,16 @@ pub struct ContextQuery {
     id: Option<u32>,
     #[validate(regex = "RE_VALID_DATE")]
     date: Option<String>,
+    #[validate(range(min = 1, max = 100))]
     per_page: Option<u32>,
     all: Option<bool>,
     bets: Option<bool>,
     favs: Option<bool>,
     potential_bets: Option<bool>,
+    season: Option<u32>,
+    league: Option<u32>,
+    status: Option<BetStatus>,
+    rank_by: Option<RankBy>,
+    group_id: Option<u32>,
+    window: Option<TimeWindow>,
 }
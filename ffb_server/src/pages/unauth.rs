@@ -9,9 +9,11 @@ use chrono::{DateTime, Utc};
 use ffb_auth::JwtUser;
 use ffb_structs::{
     game::Entity as GameEntity, game::EntityBuilder as GameEntityBuilder, game::Model as Game,
-    info, info::Model as Info, scoreboard::EntityBuilder as ScoreboardBuilder,
-    scoreboard::Model as Scoreboard, season, user,
+    group, info, info::Model as Info, scoreboard::EntityBuilder as ScoreboardBuilder,
+    scoreboard::Model as Scoreboard, season, season::EntityBuilder as SeasonBuilder,
+    season::Model as Season, season::SettlementPolicy, user,
 };
+use std::collections::HashSet;
 
 #[derive(Template)]
 #[template(path = "games/game_row.html")]
@@ -19,12 +21,24 @@ struct GamesRowTemplate {
     games: Vec<Game>,
     user_role: u32,
     current_season_id: u32,
+    seasons: Vec<Season>,
     now: DateTime<Utc>,
     fetched_date: String,
     title: String,
     fetched_on: Option<String>,
     app_data: web::Data<ApplicationData>,
     user: Option<JwtUser>,
+    friend_ids: HashSet<u32>,
+}
+
+impl GamesRowTemplate {
+    /// Get the settlement policy of the given season.
+    fn get_settlement_policy(&self, season_id: &u32) -> Option<SettlementPolicy> {
+        self.seasons
+            .iter()
+            .find(|season| &season.id == season_id)
+            .map(|season| season.settlement_policy)
+    }
 }
 
 #[derive(Template)]
@@ -70,12 +84,14 @@ pub async fn index(
                     user_role: jwt_user.role,
                     now,
                     current_season_id,
+                    seasons: SeasonBuilder::build().finish().await?,
                     app_data: app_data.clone(),
                     fetched_on: GameEntity::get_last_fetched_timestamp_for_date(
                         &now_as_simple_date,
                     )?,
                     fetched_date: now_as_simple_date,
                     user: Some(jwt_user.clone()),
+                    friend_ids: group::Entity::get_group_mate_ids(jwt_user.id).await?,
                 }),
                 true => None,
             };
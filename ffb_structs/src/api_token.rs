@@ -12,31 +12,269 @@
 //! 3. The token is returned to the crate
 //! 4. The crate with its call update the number of call the token he used can
 //! be still done with [Entity::update_threshold]
+//!
+//! The calls remaining of each token are reset to its daily quota once the
+//! quota of the API provider has been renewed, at midnight UTC. A token
+//! rejected by the API provider is disabled, so that it isn't used anymore
+//! until it is reset.
 
 use crate::database::Database;
 use crate::error::ApplicationError;
+#[cfg(feature = "cli")]
+use chrono::Utc;
+#[cfg(feature = "cli")]
+use std::collections::HashMap;
+
+/// The daily quota of the tokens registered without one, the one of the
+/// free plan of the API provider.
+pub const DEFAULT_DAILY_QUOTA: i32 = 100;
+/// The time in seconds after which the lock of a reset expires, in case the
+/// process resetting the tokens died.
+#[cfg(feature = "cli")]
+const RESET_LOCK_EXPIRATION: u64 = 60;
+
+/// A token as it is registered.
+#[derive(Debug, Clone)]
+pub struct Model {
+    /// The token itself.
+    pub token: String,
+    /// The number of calls that can still be done with the token today, none
+    /// if it is disabled.
+    pub remaining_calls: Option<i32>,
+    /// The number of calls that can be done with the token each day.
+    pub daily_quota: i32,
+    /// Why the token has been disabled, if it is.
+    pub disabled_reason: Option<String>,
+}
 
 pub struct Entity;
 
 impl Entity {
     /// Register a new token within the redis database.
     ///
-    /// Every token is stored within a ZSET with an initial threshold of 100.
+    /// Every token is stored within a ZSET with an initial threshold of its
+    /// daily quota.
     ///
     /// # Arguments
     ///
     /// - token : The token to register.
+    /// - daily_quota : The number of calls that can be done with the token
+    ///   each day.
     #[cfg(feature = "cli")]
-    pub fn register(token: &str) -> Result<(), ApplicationError> {
+    pub fn register(token: &str, daily_quota: i32) -> Result<(), ApplicationError> {
         let mut conn = Database::acquire_redis_connection()?;
+        redis::cmd("HSET")
+            .arg("api_token::quota")
+            .arg(token)
+            .arg(daily_quota)
+            .query(&mut conn)?;
+        redis::cmd("HDEL")
+            .arg("api_token::disabled")
+            .arg(token)
+            .query(&mut conn)?;
         redis::cmd("ZADD")
             .arg("api_token")
-            .arg(100)
+            .arg(daily_quota)
             .arg(token)
             .query(&mut conn)?;
         Ok(())
     }
 
+    /// Get all the registered tokens, the disabled ones included.
+    #[cfg(feature = "cli")]
+    pub fn get_all() -> Result<Vec<Model>, ApplicationError> {
+        Self::reset_if_renewed()?;
+        let mut conn = Database::acquire_redis_connection()?;
+        let active: Vec<(String, i32)> = redis::cmd("ZRANGE")
+            .arg("api_token")
+            .arg(0)
+            .arg(-1)
+            .arg("WITHSCORES")
+            .query(&mut conn)?;
+        let quotas: HashMap<String, i32> = redis::cmd("HGETALL")
+            .arg("api_token::quota")
+            .query(&mut conn)?;
+        let disabled: HashMap<String, String> = redis::cmd("HGETALL")
+            .arg("api_token::disabled")
+            .query(&mut conn)?;
+        let get_daily_quota = |token: &str| *quotas.get(token).unwrap_or(&DEFAULT_DAILY_QUOTA);
+        let mut models: Vec<Model> = active
+            .into_iter()
+            .rev()
+            .map(|(token, remaining_calls)| Model {
+                daily_quota: get_daily_quota(&token),
+                token,
+                remaining_calls: Some(remaining_calls),
+                disabled_reason: None,
+            })
+            .collect();
+        models.extend(disabled.into_iter().map(|(token, reason)| Model {
+            daily_quota: get_daily_quota(&token),
+            token,
+            remaining_calls: None,
+            disabled_reason: Some(reason),
+        }));
+        Ok(models)
+    }
+
+    /// Remove a token.
+    ///
+    /// Returns whether the token was registered.
+    ///
+    /// # Arguments
+    ///
+    /// - token : the token to remove.
+    #[cfg(feature = "cli")]
+    pub fn remove(token: &str) -> Result<bool, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let mut removed: i32 = redis::cmd("ZREM")
+            .arg("api_token")
+            .arg(token)
+            .query(&mut conn)?;
+        removed += redis::cmd("HDEL")
+            .arg("api_token::disabled")
+            .arg(token)
+            .query::<i32>(&mut conn)?;
+        redis::cmd("HDEL")
+            .arg("api_token::quota")
+            .arg(token)
+            .query(&mut conn)?;
+        Ok(removed != 0)
+    }
+
+    /// Disable a token, so that it isn't used anymore until it is reset.
+    ///
+    /// Returns whether the token was in use.
+    ///
+    /// # Arguments
+    ///
+    /// - token : the token to disable.
+    /// - reason : why the token is disabled.
+    #[cfg(feature = "cli")]
+    pub fn disable(token: &str, reason: &str) -> Result<bool, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let removed: i32 = redis::cmd("ZREM")
+            .arg("api_token")
+            .arg(token)
+            .query(&mut conn)?;
+        if removed != 0 {
+            redis::cmd("HSET")
+                .arg("api_token::disabled")
+                .arg(token)
+                .arg(reason)
+                .query(&mut conn)?;
+            warn!("Token {} disabled : {}", token, reason);
+        }
+        Ok(removed != 0)
+    }
+
+    /// Reset the calls remaining of a token to its daily quota.
+    ///
+    /// A disabled token is enabled again. Returns whether the token is
+    /// registered.
+    ///
+    /// # Arguments
+    ///
+    /// - token : the token to reset.
+    #[cfg(feature = "cli")]
+    pub fn reset(token: &str) -> Result<bool, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let daily_quota: Option<i32> = redis::cmd("HGET")
+            .arg("api_token::quota")
+            .arg(token)
+            .query(&mut conn)?;
+        let enabled: i32 = redis::cmd("HDEL")
+            .arg("api_token::disabled")
+            .arg(token)
+            .query(&mut conn)?;
+        let remaining_calls: Option<i32> = redis::cmd("ZSCORE")
+            .arg("api_token")
+            .arg(token)
+            .query(&mut conn)?;
+        // The tokens registered before their quota was stored have none.
+        if enabled == 0 && remaining_calls.is_none() && daily_quota.is_none() {
+            return Ok(false);
+        }
+        redis::cmd("ZADD")
+            .arg("api_token")
+            .arg(daily_quota.unwrap_or(DEFAULT_DAILY_QUOTA))
+            .arg(token)
+            .query(&mut conn)?;
+        Ok(true)
+    }
+
+    /// Reset the calls remaining of all the tokens in use to their daily
+    /// quota.
+    ///
+    /// The disabled tokens stay disabled.
+    #[cfg(feature = "cli")]
+    pub fn reset_all() -> Result<(), ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let tokens: Vec<String> = redis::cmd("ZRANGE")
+            .arg("api_token")
+            .arg(0)
+            .arg(-1)
+            .query(&mut conn)?;
+        for token in tokens {
+            Self::reset(&token)?;
+        }
+        debug!("Calls remaining of the tokens reset");
+        Ok(())
+    }
+
+    /// Reset the calls remaining of the tokens if the quota of the API
+    /// provider has been renewed since the last reset.
+    ///
+    /// The quota is renewed every day at midnight UTC. The date of the last
+    /// reset is only stored once the reset has succeeded, so that a failed
+    /// reset is tried again on the next call.
+    #[cfg(feature = "cli")]
+    fn reset_if_renewed() -> Result<(), ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let today: String = Utc::now().naive_utc().date().to_string();
+        let last_reset: Option<String> = redis::cmd("GET")
+            .arg("api_token::last_reset")
+            .query(&mut conn)?;
+        if last_reset.as_deref() == Some(today.as_str()) {
+            return Ok(());
+        }
+        // The lock makes sure that only one process resets the tokens.
+        let locked: Option<String> = redis::cmd("SET")
+            .arg("api_token::reset_in_progress")
+            .arg(&today)
+            .arg("NX")
+            .arg("EX")
+            .arg(RESET_LOCK_EXPIRATION)
+            .query(&mut conn)?;
+        if locked.is_none() {
+            debug!("The tokens are already being reset by another process");
+            return Ok(());
+        }
+        info!("The daily quota has been renewed, the tokens are reset");
+        let reset_result = Self::reset_all();
+        if reset_result.is_ok() {
+            redis::cmd("SET")
+                .arg("api_token::last_reset")
+                .arg(&today)
+                .query(&mut conn)?;
+        }
+        redis::cmd("DEL")
+            .arg("api_token::reset_in_progress")
+            .query(&mut conn)?;
+        reset_result
+    }
+
+    /// Get the number of calls that can still be done today with all the
+    /// tokens in use.
+    #[cfg(feature = "cli")]
+    pub fn get_remaining_calls() -> Result<i64, ApplicationError> {
+        Ok(Self::get_all()?
+            .iter()
+            .filter_map(|model| model.remaining_calls)
+            .map(i64::from)
+            .sum())
+    }
+
     /// Retrieves the token with the most calls remaining.
     ///
     /// This method gets the most from the fact that the token is stored within
@@ -44,6 +282,7 @@ impl Entity {
     /// quickly which token has the most calls remaining.
     #[cfg(feature = "cli")]
     pub fn get_token() -> Result<String, ApplicationError> {
+        Self::reset_if_renewed()?;
         let mut conn = Database::acquire_redis_connection()?;
         let result: Vec<String> = redis::cmd("ZRANGE")
             .arg("api_token")
@@ -63,6 +302,8 @@ impl Entity {
     /// Given the API used by this app is in a freemium model, the threshold has
     /// to be updated pretty frequently.
     ///
+    /// A token removed or disabled in the meantime isn't added back.
+    ///
     /// # Arguments
     ///
     /// - token : the token whose threshold has to be updated.
@@ -70,12 +311,9 @@ impl Entity {
     #[cfg(feature = "cli")]
     pub fn update_threshold(token: &str, threshold: i32) -> Result<(), ApplicationError> {
         let mut conn = Database::acquire_redis_connection()?;
-        redis::cmd("ZREM")
-            .arg("api_token")
-            .arg(token)
-            .query(&mut conn)?;
         redis::cmd("ZADD")
             .arg("api_token")
+            .arg("XX")
             .arg(threshold)
             .arg(token)
             .query(&mut conn)?;
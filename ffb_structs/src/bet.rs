@@ -2,19 +2,30 @@
 //!
 //! The common logic behind this struct is that it is stored mainly in MySQL
 //! with a replication in Mongo so that once the game result is known, the
-//! user either earns 100 times is stake given he had the right results, 0
-//! points otherwise.
+//! bet is scored with the season's scoring rule, see [ScoringRule].
 
+use crate::bet_history;
+use crate::bet_slip;
+use crate::challenge;
+use crate::common_api_structs::Goals;
 use crate::database::Database;
 use crate::error::ApplicationError;
 use crate::game;
 use crate::game::Model as Game;
+use crate::ledger;
+use crate::ledger::BetRef;
+use crate::market_bet;
+use crate::score_bet;
 use crate::scoreboard;
+use crate::scoring_rule::{ScoringRule, JOKER_MULTIPLIER};
 use crate::transaction_result::TransactionResult;
-use chrono::{DateTime, Utc};
+use crate::{season, season::Model as Season, season::SettlementPolicy};
+use chrono::{DateTime, Datelike, Utc};
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 use serde::{Deserialize, Serialize};
+use sqlx::{Connection, MySqlConnection, QueryBuilder};
+use std::collections::HashMap;
 
 /// A way to modelize the output of a game.
 ///
@@ -34,6 +45,56 @@ pub enum GameResult {
     Loss = 3,
 }
 
+/// The way the bets of a fixture have been settled.
+///
+/// A fixture is either settled with its result, or voided when it didn't
+/// reach any, in which case the bets made on it are refunded.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, Display)]
+pub enum Settlement {
+    /// Home team has a higher score than the away one.
+    Win,
+    /// No team scored more goals than the other one.
+    Draw,
+    /// Away team has a higher score than the home one.
+    Loss,
+    /// The fixture has been postponed, cancelled, abandoned or awarded
+    /// without a known score.
+    Void,
+}
+
+impl Settlement {
+    /// Whether the fixture has been voided or not.
+    pub fn is_void(&self) -> bool {
+        *self == Settlement::Void
+    }
+
+    /// The result the bets have to match to earn points, none if voided.
+    pub fn as_game_result(&self) -> Option<GameResult> {
+        match self {
+            Settlement::Win => Some(GameResult::Win),
+            Settlement::Draw => Some(GameResult::Draw),
+            Settlement::Loss => Some(GameResult::Loss),
+            Settlement::Void => None,
+        }
+    }
+}
+
+impl From<GameResult> for Settlement {
+    fn from(game_result: GameResult) -> Self {
+        match game_result {
+            GameResult::Win => Settlement::Win,
+            GameResult::Draw => Settlement::Draw,
+            GameResult::Loss => Settlement::Loss,
+        }
+    }
+}
+
+impl PartialEq<GameResult> for Settlement {
+    fn eq(&self, other: &GameResult) -> bool {
+        *self == Settlement::from(*other)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Model {
     /// ID of the user who made the bet.
@@ -49,7 +110,18 @@ pub struct Model {
     /// Outcome of the bet.
     ///
     /// If result_id = the real result at the time the user bet is fetched,
-    pub outcome: Option<u32>,
+    ///
+    /// Might be negative if the season's scoring rule penalizes the wrong
+    /// picks.
+    pub outcome: Option<i32>,
+    /// Whether the bet has been voided and refunded.
+    pub is_void: bool,
+    /// The id of the odds snapshot the bet has been placed against.
+    pub odds_snapshot_id: Option<String>,
+    /// The period whose joker is attached to the bet, none without joker.
+    ///
+    /// See [Entity::get_joker_period].
+    pub joker_period: Option<String>,
 }
 
 pub struct Entity;
@@ -61,8 +133,18 @@ impl Entity {
     /// processed by a subsequent call, and then will add the points to the
     /// users who won the bet.
     ///
+    /// The games are settled following the settlement policy of their season,
+    /// either on the regular time result or on the team that advances.
+    ///
+    /// The games that won't reach a result (postponed, cancelled, abandoned)
+    /// have their bets voided, so that they are refunded and left out of the
+    /// scoreboards. Awarded games are settled with the awarded score if it is
+    /// known, voided otherwise.
+    ///
     /// The information that this method has been executed is stored within
     /// the fixture structure as [crate::game::Model::processed_as].
+    ///
+    /// The challenges whose games have all been processed are then settled.
     pub async fn validate_bets() -> Result<(), ApplicationError> {
         let database = Database::acquire_mongo_connection().await?;
         let mut conn = Database::acquire_sql_connection().await?;
@@ -78,7 +160,7 @@ impl Entity {
                     "fixture.status.short" :
                     {
                         "$in":
-                            ["FT", "PEN", "AET"]
+                            ["FT", "PEN", "AET", "PST", "CANC", "ABD", "AWD", "WO"]
                     }
                 },
                 None,
@@ -86,56 +168,353 @@ impl Entity {
             .await?
             .try_collect()
             .await?;
+        let seasons: HashMap<u32, Season> = Self::get_seasons().await?;
         // Second step : We iterate over the results.
         for game in games {
-            // Third step : We check whether the score at the fulltime is
-            // existing or not, and store it within variables.
-            if let (Some(game_id), Some(score)) = (game.id, game.score.fulltime) {
-                if let (Some(home), Some(away)) = (score.home, score.away) {
-                    let result: GameResult = match home - away {
-                        v if 0 < v => GameResult::Win,
-                        v if v < 0 => GameResult::Loss,
-                        _ => GameResult::Draw,
-                    };
-                    // Fourth step : now that the result is known, we update
-                    // the user bets.
-                    let update_result = sqlx::query("UPDATE USER_BET SET outcome=IF(result_id=?, stake*100, 0) WHERE fixture_id=?")
-                        .bind(result)
-                        .bind(game.fixture.id)
-                        .execute(&mut conn)
-                        .await?;
-                    let number_of_rows_updated: u64 = update_result.rows_affected();
-                    total_number_of_rows_updated += number_of_rows_updated;
-                    // Fifth step : We report the modification within the
-                    // mongodb that the game has been processed.
-                    database
-                        .collection::<Game>("fixture")
-                        .update_one(
-                            doc! {"_id": game_id},
-                            doc! {"$set": {"processedAs": bson::to_bson(&result)?}},
-                            None,
-                        )
-                        .await?;
-                    info!("Game id {} has been processed with success with the result {} and {} user bet rows updated", game_id, result, number_of_rows_updated);
-                }
-            }
+            let game_id = match game.id {
+                Some(game_id) => game_id,
+                None => continue,
+            };
+            // Third step : We deduce the settlement from the game's status,
+            // its score and its season's settlement policy.
+            let settlement_policy: SettlementPolicy =
+                Self::get_settlement_policy(&seasons, game.season_id);
+            let settlement: Settlement = match game.get_settlement(&settlement_policy) {
+                Some(settlement) => settlement,
+                None => continue,
+            };
+            // Fourth step : now that the settlement is known, we update
+            // the user bets given the season's scoring rule.
+            let scoring_rule: ScoringRule = Self::get_scoring_rule(&seasons, game.season_id);
+            let number_of_rows_updated: u64 =
+                Self::settle_bets(&mut conn, &game, &settlement, &scoring_rule).await?;
+            total_number_of_rows_updated += number_of_rows_updated;
+            // Fifth step : We report the modification within the
+            // mongodb that the game has been processed.
+            database
+                .collection::<Game>("fixture")
+                .update_one(
+                    doc! {"_id": game_id},
+                    doc! {"$set": {
+                        "processedAs": bson::to_bson(&settlement)?,
+                        "processedScore": bson::to_bson(&game.score.fulltime)?,
+                    }},
+                    None,
+                )
+                .await?;
+            info!(
+                "Game id {} has been processed with success as {} and {} user bet rows updated",
+                game_id, settlement, number_of_rows_updated
+            );
         }
+        // Sixth step : the challenges whose games have all been processed
+        // are settled, and the unanswered ones whose games have started
+        // expire.
+        let number_of_challenges_settled: u64 =
+            challenge::Entity::settle_challenges(&mut conn).await?;
+        let number_of_challenges_expired: u64 =
+            challenge::Entity::expire_challenges(&mut conn).await?;
         debug!("The bet validaiton process has completed with success");
         debug!("Number of rows updated : {}", total_number_of_rows_updated);
-        // Sixth step if appliable : we clear the cache of the leaderboard
+        debug!(
+            "Number of challenges settled : {}",
+            number_of_challenges_settled
+        );
+        debug!(
+            "Number of challenges expired : {}",
+            number_of_challenges_expired
+        );
+        // Seventh step if appliable : we clear the cache of the leaderboard
         // given the bets have been updated.
         if total_number_of_rows_updated != 0 {
             scoreboard::Entity::clear_cache()?;
+            bet_history::Entity::clear_cache()?;
+        }
+        Ok(())
+    }
+
+    /// Settle again the bets of the games whose score has been corrected.
+    ///
+    /// The remote API sometimes corrects a final score after the bets have
+    /// been validated. This method looks up the processed games whose
+    /// settlement doesn't match their stored score anymore, and recomputes
+    /// the outcome of the bets made on them.
+    ///
+    /// Each game is settled within a transaction, and every outcome change is
+    /// logged.
+    pub async fn resettle_bets() -> Result<(), ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut total_number_of_rows_updated: u64 = 0;
+        // The games processed before their score was stored are given their
+        // current one, so that the next corrections are detected.
+        let backfill_result = database
+            .collection::<Game>("fixture")
+            .update_many(
+                doc! {
+                    "processedAs" : {"$ne": null},
+                    "processedScore": {"$exists": false}
+                },
+                vec![doc! {"$set": {"processedScore": "$score.fulltime"}}],
+                None,
+            )
+            .await?;
+        debug!(
+            "Number of processed scores backfilled : {}",
+            backfill_result.modified_count
+        );
+        let games: Vec<Game> = database
+            .collection::<Game>("fixture")
+            .find(
+                doc! {
+                    "processedAs" : {"$ne": null},
+                    "seasonId": {"$ne": null},
+                    "fixture.status.short" :
+                    {
+                        "$in":
+                            ["FT", "PEN", "AET", "PST", "CANC", "ABD", "AWD", "WO"]
+                    }
+                },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?;
+        let seasons: HashMap<u32, Season> = Self::get_seasons().await?;
+        for game in games {
+            let settlement_policy: SettlementPolicy =
+                Self::get_settlement_policy(&seasons, game.season_id);
+            let scoring_rule: ScoringRule = Self::get_scoring_rule(&seasons, game.season_id);
+            // A game processed without any score is settled again once it
+            // has one.
+            let score_corrected: bool = game.processed_score != game.score.fulltime;
+            let (game_id, processed_as, settlement) = match (
+                game.id,
+                game.processed_as,
+                game.get_settlement(&settlement_policy),
+            ) {
+                (Some(game_id), Some(processed_as), Some(settlement))
+                    if processed_as != settlement || score_corrected =>
+                {
+                    (game_id, processed_as, settlement)
+                }
+                _ => continue,
+            };
+            warn!(
+                "Game id {} has been processed as {} but is now settled as {}",
+                game_id, processed_as, settlement
+            );
+            let mut tx = conn.begin().await?;
+            let previous_outcomes: Vec<(String, u32, Option<i64>)> =
+                Self::get_outcomes(&mut tx, game.fixture.id).await?;
+            let number_of_rows_updated: u64 =
+                Self::settle_bets(&mut tx, &game, &settlement, &scoring_rule).await?;
+            let outcomes: Vec<(String, u32, Option<i64>)> =
+                Self::get_outcomes(&mut tx, game.fixture.id).await?;
+            // The challenges already settled on the game are settled again.
+            challenge::Entity::resettle_challenges(&mut tx, game.fixture.id).await?;
+            tx.commit().await?;
+            for (kind, user_id, outcome) in outcomes {
+                let previous_outcome: Option<i64> = previous_outcomes
+                    .iter()
+                    .find(|(previous_kind, previous_user_id, _)| {
+                        *previous_kind == kind && *previous_user_id == user_id
+                    })
+                    .and_then(|(_, _, previous_outcome)| *previous_outcome);
+                if previous_outcome != outcome {
+                    info!(
+                        "The outcome of the {} of user {} on fixture {} changed from {:?} to {:?}",
+                        kind, user_id, game.fixture.id, previous_outcome, outcome
+                    );
+                }
+            }
+            total_number_of_rows_updated += number_of_rows_updated;
+            database
+                .collection::<Game>("fixture")
+                .update_one(
+                    doc! {"_id": game_id},
+                    doc! {"$set": {
+                        "processedAs": bson::to_bson(&settlement)?,
+                        "processedScore": bson::to_bson(&game.score.fulltime)?,
+                    }},
+                    None,
+                )
+                .await?;
+            info!(
+                "Game id {} has been settled again as {} and {} user bet rows updated",
+                game_id, settlement, number_of_rows_updated
+            );
+        }
+        debug!("The bet resettlement process has completed with success");
+        debug!("Number of rows updated : {}", total_number_of_rows_updated);
+        if total_number_of_rows_updated != 0 {
+            scoreboard::Entity::clear_cache()?;
+            bet_history::Entity::clear_cache()?;
         }
         Ok(())
     }
 
+    /// Get every season, indexed by season id.
+    async fn get_seasons() -> Result<HashMap<u32, Season>, ApplicationError> {
+        let seasons: Vec<Season> = season::EntityBuilder::build().finish().await?;
+        Ok(seasons
+            .into_iter()
+            .map(|season| (season.id, season))
+            .collect())
+    }
+
+    /// Get the settlement policy of the given season.
+    ///
+    /// The games are settled on the regular time if the season is unknown.
+    fn get_settlement_policy(
+        seasons: &HashMap<u32, Season>,
+        season_id: Option<u32>,
+    ) -> SettlementPolicy {
+        season_id
+            .and_then(|season_id| seasons.get(&season_id))
+            .map(|season| season.settlement_policy)
+            .unwrap_or(SettlementPolicy::RegularTime)
+    }
+
+    /// Get the scoring rule of the given season.
+    ///
+    /// The bets are weighted by their odds if the season is unknown.
+    fn get_scoring_rule(seasons: &HashMap<u32, Season>, season_id: Option<u32>) -> ScoringRule {
+        season_id
+            .and_then(|season_id| seasons.get(&season_id))
+            .map(|season| season.scoring_rule)
+            .unwrap_or(ScoringRule::OddsWeighted)
+    }
+
+    /// Get the outcome of every bet and score prediction made on a fixture.
+    ///
+    /// The rows are locked until the end of the transaction.
+    async fn get_outcomes(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+    ) -> Result<Vec<(String, u32, Option<i64>)>, ApplicationError> {
+        let mut outcomes: Vec<(String, u32, Option<i64>)> = sqlx::query_as(
+            "SELECT 'bet', user_id, CAST(outcome AS SIGNED) FROM USER_BET WHERE fixture_id=? FOR UPDATE",
+        )
+        .bind(fixture_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let score_outcomes: Vec<(String, u32, Option<i64>)> = sqlx::query_as(
+            "SELECT 'score prediction', user_id, CAST(outcome AS SIGNED) FROM USER_SCORE_BET WHERE fixture_id=? FOR UPDATE",
+        )
+        .bind(fixture_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        outcomes.extend(score_outcomes);
+        let market_outcomes: Vec<(String, u32, Option<i64>)> = sqlx::query_as(
+            "SELECT 'market bet', user_id, CAST(outcome AS SIGNED) FROM USER_MARKET_BET WHERE fixture_id=? FOR UPDATE",
+        )
+        .bind(fixture_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        outcomes.extend(market_outcomes);
+        let slip_outcomes: Vec<(String, u32, Option<i64>)> = sqlx::query_as(
+            "SELECT 'accumulator', user_id, CAST(outcome AS SIGNED) FROM BET_SLIP WHERE id IN (SELECT slip_id FROM USER_BET WHERE fixture_id=?) FOR UPDATE",
+        )
+        .bind(fixture_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        outcomes.extend(slip_outcomes);
+        Ok(outcomes)
+    }
+
+    /// Update the outcome of the bets, score predictions, market bets and
+    /// accumulators made on a game given its settlement.
+    ///
+    /// Returns the number of bets and predictions updated.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - game : the game whose bets are settled.
+    /// - settlement : how the game has been settled.
+    /// - scoring_rule : the rule used to score the bets.
+    async fn settle_bets(
+        conn: &mut MySqlConnection,
+        game: &Game,
+        settlement: &Settlement,
+        scoring_rule: &ScoringRule,
+    ) -> Result<u64, ApplicationError> {
+        let update_result = match settlement.as_game_result() {
+            Some(result) => {
+                // The outcome of the bets the joker is attached to is
+                // multiplied.
+                let mut query_builder =
+                    QueryBuilder::new("UPDATE USER_BET SET outcome=IF(joker_period IS NULL, 1, ");
+                query_builder.push_bind(JOKER_MULTIPLIER).push(")*");
+                scoring_rule.push_outcome(&mut query_builder, result);
+                query_builder
+                    .push(", is_void=0 WHERE fixture_id=")
+                    .push_bind(game.fixture.id);
+                query_builder.build().execute(&mut *conn).await?
+            }
+            None => {
+                sqlx::query("UPDATE USER_BET SET outcome=0, is_void=1 WHERE fixture_id=?")
+                    .bind(game.fixture.id)
+                    .execute(&mut *conn)
+                    .await?
+            }
+        };
+        // The score predictions and the market bets are graded on the score
+        // after 90 minutes of playtime, whatever the season's settlement
+        // policy is. They are left pending until the score is known, and
+        // settled once it is through [Entity::resettle_bets].
+        let score: Option<(i16, i16)> = match settlement {
+            Settlement::Void => None,
+            _ => game.score.fulltime.as_ref().and_then(Goals::as_pair),
+        };
+        let mut number_of_side_bets_updated: u64 = 0;
+        if settlement.is_void() || score.is_some() {
+            number_of_side_bets_updated += score_bet::Entity::settle_score_bets(
+                &mut *conn,
+                game.fixture.id,
+                score,
+                scoring_rule,
+            )
+            .await?;
+            number_of_side_bets_updated += market_bet::Entity::settle_market_bets(
+                &mut *conn,
+                game.fixture.id,
+                score,
+                scoring_rule,
+            )
+            .await?;
+        } else {
+            warn!(
+                "Game id {} has been settled without its fulltime score, its score predictions and market bets are left pending",
+                game.fixture.id
+            );
+        }
+        // The winnings are credited on the users' balance.
+        ledger::Entity::settle_fixture(&mut *conn, game.fixture.id).await?;
+        // The accumulators are settled once the legs have been.
+        let number_of_slips_updated: u64 =
+            bet_slip::Entity::settle_bet_slips(&mut *conn, game.fixture.id, scoring_rule).await?;
+        ledger::Entity::settle_slips(conn, game.fixture.id).await?;
+        Ok(update_result.rows_affected() + number_of_side_bets_updated + number_of_slips_updated)
+    }
+
     /// Upsert a bet within the database.
     ///
     /// When a user wants to bet on a fixture that has odds stored and is
     /// indicated as a bet, the user can bet on the game and its outcome
     /// through this method.
     ///
+    /// The season and the stake are never trusted from the caller : the bet
+    /// is attached to the current season, and the stake is the odd stored on
+    /// the game for the given result.
+    ///
+    /// The amount is taken from the user's balance for the season, the bet
+    /// is refused if the balance isn't sufficient.
+    ///
+    /// The user's joker can be attached to the bet to double its outcome, as
+    /// long as it hasn't been attached to another bet of the same period.
+    ///
     /// Once this method is called, the result is stored within the SQL DB
     /// besides of being replicated in Mongo.
     ///
@@ -143,17 +522,65 @@ impl Entity {
     ///
     /// - user_id : the id of the user who makes the bet.
     /// - fixture_id : the id of the fixture the user bets on.
-    /// - season_id : the id of the current season, used to know on which
-    /// season the outcome of the game will be rattached.
     /// - game_result : the bet of the user on the fixture.
-    /// - stake : the original odds on the game.
+    /// - amount : the amount of the user's balance put on the bet.
+    /// - joker : whether the user attaches his joker to the bet.
     pub async fn upsert_bet(
         user_id: u32,
         fixture_id: u32,
-        season_id: u32,
         game_result: GameResult,
-        stake: f32,
+        amount: u32,
+        joker: bool,
     ) -> Result<TransactionResult, ApplicationError> {
+        let (season, game) = Self::validate_open_bet(user_id, fixture_id).await?;
+        let season_id: u32 = season.id;
+        let league_id: u32 = game.league.id;
+        let mut conn = Database::acquire_sql_connection().await?;
+        // The legs of an accumulator can't be changed afterwards.
+        let slip_id: Option<(Option<u32>,)> =
+            sqlx::query_as("SELECT slip_id FROM USER_BET WHERE user_id=? AND fixture_id=?")
+                .bind(user_id)
+                .bind(fixture_id)
+                .fetch_optional(&mut conn)
+                .await?;
+        if let Some((Some(slip_id),)) = slip_id {
+            warn!(
+                "User {} has tried to change the leg of accumulator {} on game {}",
+                user_id, slip_id, fixture_id
+            );
+            return Err(ApplicationError::AlreadyBet(fixture_id));
+        }
+        let joker_period: Option<String> = match joker {
+            true => Some(Self::get_joker_period(&game)),
+            false => None,
+        };
+        let stake: f32 = game
+            .odds
+            .ok_or(ApplicationError::NoOddsForGame(fixture_id))?
+            .get_odd_for_result(&game_result);
+        let odds_snapshot_id: Option<String> = game
+            .odds_snapshot_id
+            .map(|snapshot_id| snapshot_id.to_hex());
+        let mut tx = conn.begin().await?;
+        if let Some(joker_period) = &joker_period {
+            let joker_fixture_id: Option<(u32,)> = sqlx::query_as(
+                "SELECT fixture_id FROM USER_BET WHERE user_id=? AND season_id=? AND joker_period=? AND fixture_id<>? FOR UPDATE",
+            )
+            .bind(user_id)
+            .bind(season_id)
+            .bind(joker_period)
+            .bind(fixture_id)
+            .fetch_optional(&mut tx)
+            .await?;
+            if let Some((joker_fixture_id,)) = joker_fixture_id {
+                warn!(
+                    "User {} has tried to use the joker of {} already used on game {}",
+                    user_id, joker_period, joker_fixture_id
+                );
+                return Err(ApplicationError::JokerAlreadyUsed(joker_fixture_id));
+            }
+        }
+        ledger::Entity::stake(&mut tx, user_id, season_id, BetRef::Bet(fixture_id), amount).await?;
         let now: DateTime<Utc> = Utc::now();
         let database = Database::acquire_mongo_connection().await?;
         // We store the result of the update request since we update a bet
@@ -171,7 +598,9 @@ impl Entity {
                 },
                 doc! {
                     "$set": {
-                        "betters.$.game_result": bson::to_bson(&game_result)?
+                        "betters.$.game_result": bson::to_bson(&game_result)?,
+                        "betters.$.amount": amount,
+                        "betters.$.joker": joker
                     }
                 },
                 None,
@@ -193,7 +622,9 @@ impl Entity {
                         "$addToSet": {
                             "betters" :{
                                 "user_id": user_id,
-                                "game_result": bson::to_bson(&game_result)?
+                                "game_result": bson::to_bson(&game_result)?,
+                                "amount": amount,
+                                "joker": joker
                             }
                         }
                     },
@@ -208,26 +639,97 @@ impl Entity {
                 return Err(ApplicationError::FormOutdated);
             }
         }
-        let mut conn = Database::acquire_sql_connection().await?;
         let result = sqlx::query(
-            "INSERT INTO USER_BET(user_id, fixture_id, result_id, season_id, stake) VALUES(?,?,?,?,?) ON DUPLICATE KEY UPDATE result_id=?, stake=?",
+            "INSERT INTO USER_BET(user_id, fixture_id, league_id, result_id, season_id, stake, odds_snapshot_id, joker_period) VALUES(?,?,?,?,?,?,?,?) ON DUPLICATE KEY UPDATE result_id=?, stake=?, odds_snapshot_id=?, joker_period=?",
         )
         .bind(user_id)
         .bind(fixture_id)
+        .bind(league_id)
         .bind(&game_result)
         .bind(season_id)
         .bind(stake)
+        .bind(&odds_snapshot_id)
+        .bind(&joker_period)
         .bind(&game_result)
         .bind(stake)
-        .execute(&mut conn)
+        .bind(&odds_snapshot_id)
+        .bind(&joker_period)
+        .execute(&mut tx)
         .await?;
+        tx.commit().await?;
         debug!(
-            "The user {} has bet on game {} with output {}",
-            user_id, fixture_id, game_result
+            "The user {} has bet {} on game {} with output {}",
+            user_id, amount, fixture_id, game_result
         );
         game::Entity::clear_cache()?;
+        bet_history::Entity::clear_cache()?;
         Ok(TransactionResult::expect_single_result(
             result.rows_affected(),
         ))
     }
+
+    /// Check that a user can bet on a game of the current season.
+    ///
+    /// The current season has to be open, and the game has to be a bet of
+    /// it. Returns the current season and the game.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user who bets.
+    /// - fixture_id : the id of the fixture the user bets on.
+    pub(crate) async fn validate_open_bet(
+        user_id: u32,
+        fixture_id: u32,
+    ) -> Result<(Season, Game), ApplicationError> {
+        let season_id: u32 = season::Entity::get_current_season_id().await?;
+        let season: Season = season::Entity::find_by_id(season_id)
+            .await?
+            .ok_or(ApplicationError::SeasonClosed(season_id))?;
+        if season.is_closed {
+            warn!(
+                "User {} has tried to bet on the closed season {}",
+                user_id, season_id
+            );
+            return Err(ApplicationError::SeasonClosed(season_id));
+        }
+        let game: Game = game::Entity::find_by_fixture_id(fixture_id)
+            .await?
+            .ok_or(ApplicationError::GameNotFound(fixture_id))?;
+        match game.season_id {
+            None => {
+                warn!(
+                    "User {} has tried to bet on game {} that isn't a bet",
+                    user_id, fixture_id
+                );
+                Err(ApplicationError::GameNotABet(fixture_id))
+            }
+            // A game opened in a previous season can't be bet on anymore.
+            Some(game_season_id) if game_season_id != season_id => {
+                warn!(
+                    "User {} has tried to bet on game {} of the previous season {}",
+                    user_id, fixture_id, game_season_id
+                );
+                Err(ApplicationError::SeasonClosed(game_season_id))
+            }
+            Some(_) => Ok((season, game)),
+        }
+    }
+
+    /// Get the period a joker can be used once in for the given game.
+    ///
+    /// The period is the league's round the game belongs to, or the ISO week
+    /// of its kickoff if the round is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// - game : the game the joker is attached to.
+    fn get_joker_period(game: &Game) -> String {
+        match &game.league.round {
+            Some(round) => format!("{}:{}", game.league.id, round),
+            None => {
+                let week = game.fixture.date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
 }
@@ -0,0 +1,297 @@
+//! The bet history is the paginated list of bets a user has made.
+//!
+//! The bets are read from the USER_BET table, and completed with the Mongo
+//! fixtures they stand on. The legs of the accumulators are left out as they
+//! don't earn points on their own. The history can be filtered by season, league and
+//! status, and comes with the totals of the filtered bets.
+//!
+//! The history is cached until a bet is made or the bets are validated.
+
+use crate::bet_history_entry::{BetStatus, Model as BetHistoryEntry, Row as BetHistoryRow};
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::game::Model as Game;
+use futures::{StreamExt, TryStreamExt};
+use mongodb::bson::{doc, Document};
+use serde::{Deserialize, Serialize};
+use sqlx::{mysql::MySqlRow, FromRow, MySql, QueryBuilder};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The totals of the bets matching the history's filters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Totals {
+    /// The number of bets made, the voided ones excluded.
+    pub bets_made: i64,
+    /// The number of bets whose fixture hasn't been processed yet.
+    pub pending: i64,
+    /// The number of bets that earned points.
+    pub won: i64,
+    /// The number of bets that didn't earn any point.
+    pub lost: i64,
+    /// The number of bets that have been voided.
+    pub voided: i64,
+    /// The total of points earned.
+    pub points: bigdecimal::BigDecimal,
+}
+
+/// A league a user has bet on, used to filter the history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BetLeague {
+    /// The remote API's league id.
+    #[serde(rename = "_id")]
+    pub id: u32,
+    /// The league's name.
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Model {
+    /// The bets of the requested page, the most recent first.
+    pub entries: Vec<BetHistoryEntry>,
+    /// The totals for all the bets matching the filters.
+    pub totals: Totals,
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Get the leagues a user has bet on.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub async fn get_bet_leagues(user_id: u32) -> Result<Vec<BetLeague>, ApplicationError> {
+        let leagues: Vec<BetLeague> =
+            Self::aggregate_bet_leagues(doc! {"betters.user_id": user_id}).await?;
+        debug!("The leagues user {} has bet on have been fetched", user_id);
+        Ok(leagues)
+    }
+
+    /// Get the leagues any user has bet on.
+    pub async fn get_all_bet_leagues() -> Result<Vec<BetLeague>, ApplicationError> {
+        let leagues: Vec<BetLeague> =
+            Self::aggregate_bet_leagues(doc! {"betters.0": {"$exists": true}}).await?;
+        debug!("The leagues bet on have been fetched");
+        Ok(leagues)
+    }
+
+    /// Get the leagues of the fixtures matching the given filter, by name.
+    ///
+    /// # Arguments
+    ///
+    /// - filter : the filter the fixtures have to match.
+    async fn aggregate_bet_leagues(filter: Document) -> Result<Vec<BetLeague>, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let mut results = database
+            .collection::<Game>("fixture")
+            .aggregate(
+                vec![
+                    doc! {"$match": filter},
+                    doc! {"$group": {"_id": "$league.id", "name": {"$first": "$league.name"}}},
+                    doc! {"$sort": {"name": 1}},
+                ],
+                None,
+            )
+            .await?;
+        let mut leagues: Vec<BetLeague> = Vec::new();
+        while let Some(result) = results.next().await {
+            leagues.push(bson::from_document(result?)?);
+        }
+        Ok(leagues)
+    }
+
+    /// Clears the cache.
+    pub(crate) fn clear_cache() -> Result<(), ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let keys_to_del: Vec<String> = redis::cmd("KEYS")
+            .arg(r#"bet_history::*"#)
+            .query(&mut conn)?;
+        if !keys_to_del.is_empty() {
+            redis::cmd("DEL").arg(keys_to_del).query(&mut conn)?;
+        }
+        debug!("The bet history's cache has been cleared");
+        Ok(())
+    }
+}
+
+#[derive(Default, Hash, Debug)]
+pub struct EntityBuilder {
+    /// The user whose bets are looked up.
+    user_id: u32,
+    /// Restrict the bets to the given season.
+    season_id: Option<u32>,
+    /// Restrict the bets to the fixtures of the given league.
+    league_id: Option<u32>,
+    /// Restrict the bets to the given status.
+    status: Option<BetStatus>,
+    /// The requested page.
+    page: u32,
+    /// The number of bets per page.
+    per_page: u32,
+}
+
+impl EntityBuilder {
+    /// Create the builder for the given user.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub fn build(user_id: u32) -> EntityBuilder {
+        EntityBuilder {
+            user_id,
+            per_page: 10,
+            ..Self::default()
+        }
+    }
+
+    /// Restrict the bets to the given season.
+    pub fn season_id(&mut self, season_id: Option<u32>) -> &mut Self {
+        self.season_id = season_id;
+        self
+    }
+
+    /// Restrict the bets to the fixtures of the given league.
+    pub fn league_id(&mut self, league_id: Option<u32>) -> &mut Self {
+        self.league_id = league_id;
+        self
+    }
+
+    /// Restrict the bets to the given status.
+    pub fn status(&mut self, status: Option<BetStatus>) -> &mut Self {
+        self.status = status;
+        self
+    }
+
+    /// The requested page, starting from 0.
+    pub fn page(&mut self, page: u32) -> &mut Self {
+        self.page = page;
+        self
+    }
+
+    /// The number of bets per page.
+    pub fn per_page(&mut self, per_page: u32) -> &mut Self {
+        self.per_page = per_page;
+        self
+    }
+
+    /// Pushes the WHERE clause matching the builder's filters.
+    fn push_filters(
+        &self,
+        query_builder: &mut QueryBuilder<MySql>,
+        fixture_ids: &Option<Vec<u32>>,
+    ) {
+        query_builder
+            .push("\nWHERE ub.user_id=")
+            .push_bind(self.user_id)
+            .push("\n\tAND ub.slip_id IS NULL");
+        if let Some(season_id) = self.season_id {
+            query_builder
+                .push("\n\tAND ub.season_id=")
+                .push_bind(season_id);
+        }
+        match self.status {
+            Some(BetStatus::Pending) => query_builder.push("\n\tAND ub.outcome IS NULL"),
+            Some(BetStatus::Won) => query_builder.push("\n\tAND 0 < ub.outcome"),
+            Some(BetStatus::Lost) => {
+                query_builder.push("\n\tAND ub.outcome <= 0 AND ub.is_void = 0")
+            }
+            Some(BetStatus::Void) => query_builder.push("\n\tAND ub.is_void = 1"),
+            None => query_builder,
+        };
+        if let Some(fixture_ids) = fixture_ids {
+            if fixture_ids.is_empty() {
+                query_builder.push("\n\tAND FALSE");
+            } else {
+                query_builder.push("\n\tAND ub.fixture_id IN (");
+                let mut separated = query_builder.separated(", ");
+                for fixture_id in fixture_ids {
+                    separated.push_bind(*fixture_id);
+                }
+                separated.push_unseparated(")");
+            }
+        }
+    }
+
+    pub async fn finish(&self) -> Result<Model, ApplicationError> {
+        let mut redis_conn = Database::acquire_redis_connection()?;
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        let redis_key: String = format!("bet_history::{:x}", hasher.finish());
+        let cache_result: Option<String> = redis::cmd("GETEX")
+            .arg(&redis_key)
+            .arg("EX")
+            .arg("300")
+            .query(&mut redis_conn)?;
+        if let Some(cache_result) = cache_result {
+            debug!("The requested bet history has been found in the cache");
+            return Ok(serde_json::from_str(&cache_result)?);
+        }
+        debug!(
+            "The requested bet history hasn't been found in the cache, looking up in the database"
+        );
+        let database = Database::acquire_mongo_connection().await?;
+        // The league is only known by the Mongo fixtures, so the fixtures ids
+        // of the league have to be fetched first.
+        let fixture_ids: Option<Vec<u32>> = match self.league_id {
+            Some(league_id) => Some(
+                database
+                    .collection::<Game>("fixture")
+                    .distinct(
+                        "fixture.id",
+                        doc! {"league.id": league_id, "betters.user_id": self.user_id},
+                        None,
+                    )
+                    .await?
+                    .iter()
+                    .filter_map(|id| id.as_i64().or_else(|| id.as_i32().map(i64::from)))
+                    .map(|id| id as u32)
+                    .collect(),
+            ),
+            None => None,
+        };
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut query_builder = QueryBuilder::new("SELECT ub.fixture_id, ub.result_id, ub.season_id, szn.name AS `season_name`, ub.stake, ub.outcome, ub.is_void, DATE_FORMAT(ub.datetime, '%Y-%m-%d %H:%i') AS `placed_on`, SUM(IF(ub.outcome IS NULL, 0, ub.outcome)) OVER (ORDER BY ub.datetime, ub.fixture_id) AS `running_points`");
+        query_builder.push("\nFROM `USER_BET` ub INNER JOIN SEASON szn ON ub.season_id = szn.id");
+        self.push_filters(&mut query_builder, &fixture_ids);
+        query_builder.push("\nORDER BY ub.datetime DESC, ub.fixture_id DESC");
+        query_builder
+            .push("\nLIMIT ")
+            .push_bind(self.per_page.saturating_mul(self.page))
+            .push(",")
+            .push_bind(self.per_page);
+        let rows: Vec<MySqlRow> = query_builder.build().fetch_all(&mut conn).await?;
+        let mut entries: Vec<BetHistoryEntry> = Vec::with_capacity(rows.len());
+        for row in rows {
+            entries.push(BetHistoryRow::from_row(&row)?.into());
+        }
+        let mut query_builder = QueryBuilder::new("SELECT CAST(IF(SUM(NOT ub.is_void) IS NULL, 0, SUM(NOT ub.is_void)) AS SIGNED) AS `bets_made`, CAST(IF(SUM(ub.outcome IS NULL) IS NULL, 0, SUM(ub.outcome IS NULL)) AS SIGNED) AS `pending`, CAST(IF(SUM(0 < ub.outcome) IS NULL, 0, SUM(0 < ub.outcome)) AS SIGNED) AS `won`, CAST(IF(SUM(ub.outcome <= 0 AND ub.is_void = 0) IS NULL, 0, SUM(ub.outcome <= 0 AND ub.is_void = 0)) AS SIGNED) AS `lost`, CAST(IF(SUM(ub.is_void) IS NULL, 0, SUM(ub.is_void)) AS SIGNED) AS `voided`, IF(SUM(ub.outcome) IS NULL, 0, SUM(ub.outcome)) AS `points`");
+        query_builder.push("\nFROM `USER_BET` ub");
+        self.push_filters(&mut query_builder, &fixture_ids);
+        let row: MySqlRow = query_builder.build().fetch_one(&mut conn).await?;
+        let totals: Totals = Totals::from_row(&row)?;
+        // The games are then joined from the Mongo database.
+        let page_fixture_ids: Vec<u32> = entries.iter().map(|entry| entry.fixture_id).collect();
+        let games: Vec<Game> = database
+            .collection::<Game>("fixture")
+            .find(doc! {"fixture.id": {"$in": page_fixture_ids}}, None)
+            .await?
+            .try_collect()
+            .await?;
+        for entry in entries.iter_mut() {
+            entry.game = games
+                .iter()
+                .find(|game| game.fixture.id == entry.fixture_id)
+                .cloned();
+        }
+        let model: Model = Model { entries, totals };
+        redis::cmd("SET")
+            .arg(&redis_key)
+            .arg(serde_json::to_string(&model)?)
+            .arg("EX")
+            .arg("300")
+            .query(&mut redis_conn)?;
+        debug!("The bet history has been cached within the redis cache");
+        Ok(model)
+    }
+}
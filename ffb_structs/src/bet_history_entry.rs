@@ -0,0 +1,99 @@
+//! A bet history entry is a MySQL row of the USER_BET table completed with
+//! the Mongo fixture it stands on.
+//!
+//! It is used to display the list of bets a user has made, whether they are
+//! still pending or already settled.
+
+use crate::bet::GameResult;
+use crate::game::Model as Game;
+use serde::{Deserialize, Serialize};
+
+/// The status of a bet.
+///
+/// A bet is pending as long as its fixture hasn't been processed, and settled
+/// once an outcome has been given to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+pub enum BetStatus {
+    /// The fixture hasn't been processed yet.
+    Pending,
+    /// The bet has been settled and earned points.
+    Won,
+    /// The bet has been settled and didn't earn any point.
+    Lost,
+    /// The fixture didn't reach a result, the bet has been refunded.
+    Void,
+}
+
+/// The row as it is stored within the MySQL database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct Row {
+    pub(crate) fixture_id: u32,
+    pub(crate) result_id: GameResult,
+    pub(crate) season_id: u32,
+    pub(crate) season_name: String,
+    pub(crate) stake: f32,
+    pub(crate) outcome: Option<i32>,
+    pub(crate) is_void: bool,
+    pub(crate) placed_on: String,
+    pub(crate) running_points: bigdecimal::BigDecimal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Model {
+    /// The fixture on which the bet stands.
+    pub fixture_id: u32,
+    /// The user's pick.
+    pub result_id: GameResult,
+    /// The season the bet is attached to.
+    pub season_id: u32,
+    /// The name of the season the bet is attached to.
+    pub season_name: String,
+    /// The stake stored at the time the bet was made.
+    pub stake: f32,
+    /// The points earned by the bet, none if it is still pending.
+    pub outcome: Option<i32>,
+    /// Whether the bet has been voided and refunded.
+    pub is_void: bool,
+    /// When the bet has been placed, as YYYY-MM-DD HH:MM.
+    pub placed_on: String,
+    /// The sum of the points earned by the user's bets up to this one.
+    pub running_points: bigdecimal::BigDecimal,
+    /// The game the bet stands on.
+    ///
+    /// Might be none if the fixture has been removed from the Mongo database.
+    pub game: Option<Game>,
+}
+
+impl Model {
+    /// Returns the status of the bet.
+    pub fn status(&self) -> BetStatus {
+        match self.outcome {
+            _ if self.is_void => BetStatus::Void,
+            None => BetStatus::Pending,
+            Some(v) if 0 < v => BetStatus::Won,
+            Some(_) => BetStatus::Lost,
+        }
+    }
+
+    /// Whether the bet has already been settled or not.
+    pub fn is_settled(&self) -> bool {
+        self.outcome.is_some()
+    }
+}
+
+impl From<Row> for Model {
+    fn from(row: Row) -> Self {
+        Model {
+            fixture_id: row.fixture_id,
+            result_id: row.result_id,
+            season_id: row.season_id,
+            season_name: row.season_name,
+            stake: row.stake,
+            outcome: row.outcome,
+            is_void: row.is_void,
+            placed_on: row.placed_on,
+            running_points: row.running_points,
+            game: None,
+        }
+    }
+}
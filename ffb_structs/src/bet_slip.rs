@@ -0,0 +1,339 @@
+//! A bet slip is a MySQL structure that represents an accumulator, a bet
+//! combining several games.
+//!
+//! Each game of the accumulator, or leg, is stored as a bet within the
+//! USER_BET table linked to the slip, so that it is settled as any other bet.
+//! The legs don't earn points on their own though : the stake of the slip is
+//! the product of the legs' odds, and the slip is scored as a single pick on
+//! this stake with the season's scoring rule, right only if every leg is won.
+//! The voided legs are dropped from the product.
+//!
+//! The amount chosen by the user is taken from the balance once for the whole
+//! slip, and the winnings are this amount times the stake.
+
+use crate::bet;
+use crate::bet::GameResult;
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::game::Model as Game;
+use crate::ledger;
+use crate::ledger::BetRef;
+use crate::scoring_rule::ScoringRule;
+use crate::season;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use sqlx::{mysql::MySqlRow, Connection, MySql, MySqlConnection, QueryBuilder, Row};
+use std::collections::HashSet;
+
+/// The minimum number of games an accumulator has to combine.
+pub const MIN_LEGS: usize = 2;
+
+/// A leg as it is stored within the MySQL database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct LegRow {
+    pub(crate) slip_id: u32,
+    pub(crate) fixture_id: u32,
+    pub(crate) result_id: GameResult,
+    pub(crate) stake: f32,
+    pub(crate) outcome: Option<i32>,
+    pub(crate) is_void: bool,
+}
+
+/// A slip as it is stored within the MySQL database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct SlipRow {
+    pub(crate) id: u32,
+    pub(crate) season_id: u32,
+    pub(crate) stake: f32,
+    pub(crate) outcome: Option<i32>,
+    pub(crate) is_void: bool,
+    pub(crate) placed_on: String,
+}
+
+/// A game of an accumulator.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Leg {
+    /// The slip the leg belongs to.
+    pub slip_id: u32,
+    /// The fixture on which the leg stands.
+    pub fixture_id: u32,
+    /// The user's pick.
+    pub result_id: GameResult,
+    /// The odd of the pick at the time the accumulator was placed.
+    pub stake: f32,
+    /// Whether the leg has been won, none until the game has been processed.
+    pub outcome: Option<i32>,
+    /// Whether the leg has been voided.
+    pub is_void: bool,
+    /// The game the leg stands on.
+    ///
+    /// Might be none if the fixture has been removed from the Mongo database.
+    pub game: Option<Game>,
+}
+
+impl Leg {
+    /// Whether the leg has been won.
+    pub fn is_won(&self) -> bool {
+        matches!(self.outcome, Some(outcome) if 0 < outcome)
+    }
+}
+
+impl From<LegRow> for Leg {
+    fn from(row: LegRow) -> Self {
+        Leg {
+            slip_id: row.slip_id,
+            fixture_id: row.fixture_id,
+            result_id: row.result_id,
+            stake: row.stake,
+            outcome: row.outcome,
+            is_void: row.is_void,
+            game: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Model {
+    /// The slip's id.
+    pub id: u32,
+    /// The associated season id.
+    pub season_id: u32,
+    /// The product of the legs' odds at the time the accumulator was placed.
+    pub stake: f32,
+    /// Outcome of the accumulator, none until all its legs are processed.
+    pub outcome: Option<i32>,
+    /// Whether all the legs have been voided.
+    pub is_void: bool,
+    /// When the accumulator has been placed, as YYYY-MM-DD HH:MM.
+    pub placed_on: String,
+    /// The games combined in the accumulator.
+    pub legs: Vec<Leg>,
+}
+
+impl Model {
+    /// Whether the accumulator has been won.
+    pub fn is_won(&self) -> bool {
+        matches!(self.outcome, Some(outcome) if 0 < outcome)
+    }
+}
+
+impl From<SlipRow> for Model {
+    fn from(row: SlipRow) -> Self {
+        Model {
+            id: row.id,
+            season_id: row.season_id,
+            stake: row.stake,
+            outcome: row.outcome,
+            is_void: row.is_void,
+            placed_on: row.placed_on,
+            legs: Vec::new(),
+        }
+    }
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Place an accumulator combining the given picks.
+    ///
+    /// Each game has to be a bet of the current season with odds, that
+    /// hasn't started yet and that the user hasn't bet on already.
+    ///
+    /// The amount is taken from the user's balance for the season, the
+    /// accumulator is refused if the balance isn't sufficient.
+    ///
+    /// Returns the id of the new slip.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user who places the accumulator.
+    /// - picks : the fixtures and the user's pick on each of them.
+    /// - amount : the amount of the user's balance put on the accumulator.
+    pub async fn place(
+        user_id: u32,
+        picks: Vec<(u32, GameResult)>,
+        amount: u32,
+    ) -> Result<u32, ApplicationError> {
+        if picks.len() < MIN_LEGS {
+            return Err(ApplicationError::NotEnoughLegs(picks.len()));
+        }
+        let mut fixture_ids: HashSet<u32> = HashSet::with_capacity(picks.len());
+        for (fixture_id, _) in &picks {
+            if !fixture_ids.insert(*fixture_id) {
+                return Err(ApplicationError::AlreadyBet(*fixture_id));
+            }
+        }
+        let season_id: u32 = season::Entity::get_current_season_id().await?;
+        let now: DateTime<Utc> = Utc::now();
+        let mut stake: f32 = 1.0;
+        let mut legs: Vec<(u32, u32, GameResult, f32, Option<String>)> =
+            Vec::with_capacity(picks.len());
+        for (fixture_id, game_result) in picks {
+            let (_, game) = bet::Entity::validate_open_bet(user_id, fixture_id).await?;
+            if game.fixture.timestamp < now.timestamp() as f64 {
+                warn!(
+                    "User {} has tried to add game {} to an accumulator after the kickoff",
+                    user_id, fixture_id
+                );
+                return Err(ApplicationError::FormOutdated);
+            }
+            let odd: f32 = game
+                .odds
+                .ok_or(ApplicationError::NoOddsForGame(fixture_id))?
+                .get_odd_for_result(&game_result);
+            stake *= odd;
+            legs.push((
+                fixture_id,
+                game.league.id,
+                game_result,
+                odd,
+                game.odds_snapshot_id
+                    .map(|snapshot_id| snapshot_id.to_hex()),
+            ));
+        }
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut tx = conn.begin().await?;
+        // A game can't be both a single bet and a leg, nor a leg of two
+        // accumulators.
+        let mut query_builder = QueryBuilder::new("SELECT fixture_id FROM USER_BET WHERE user_id=");
+        query_builder
+            .push_bind(user_id)
+            .push(" AND fixture_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for fixture_id in &fixture_ids {
+            separated.push_bind(*fixture_id);
+        }
+        separated.push_unseparated(") LIMIT 1");
+        let already_bet: Option<MySqlRow> = query_builder.build().fetch_optional(&mut tx).await?;
+        if let Some(already_bet) = already_bet {
+            return Err(ApplicationError::AlreadyBet(
+                already_bet.try_get("fixture_id")?,
+            ));
+        }
+        let slip_id: u32 =
+            sqlx::query("INSERT INTO BET_SLIP(user_id, season_id, stake) VALUES(?,?,?)")
+                .bind(user_id)
+                .bind(season_id)
+                .bind(stake)
+                .execute(&mut tx)
+                .await?
+                .last_insert_id() as u32;
+        ledger::Entity::stake(&mut tx, user_id, season_id, BetRef::Slip(slip_id), amount).await?;
+        for (fixture_id, league_id, game_result, odd, odds_snapshot_id) in legs {
+            sqlx::query(
+                "INSERT INTO USER_BET(user_id, fixture_id, league_id, result_id, season_id, stake, odds_snapshot_id, slip_id) VALUES(?,?,?,?,?,?,?,?)",
+            )
+            .bind(user_id)
+            .bind(fixture_id)
+            .bind(league_id)
+            .bind(game_result)
+            .bind(season_id)
+            .bind(odd)
+            .bind(odds_snapshot_id)
+            .bind(slip_id)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        debug!(
+            "The user {} has placed the accumulator {} with a stake of {}",
+            user_id, slip_id, stake
+        );
+        Ok(slip_id)
+    }
+
+    /// Get the accumulators placed by a user, the most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub async fn get_user_slips(user_id: u32) -> Result<Vec<Model>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let slip_rows: Vec<SlipRow> = sqlx::query_as(
+            "SELECT id, season_id, stake, outcome, is_void, DATE_FORMAT(datetime, '%Y-%m-%d %H:%i') AS `placed_on` FROM BET_SLIP WHERE user_id=? ORDER BY datetime DESC, id DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&mut conn)
+        .await?;
+        let mut slips: Vec<Model> = slip_rows.into_iter().map(Model::from).collect();
+        let leg_rows: Vec<LegRow> = sqlx::query_as(
+            "SELECT slip_id, fixture_id, result_id, stake, outcome, is_void FROM USER_BET WHERE user_id=? AND slip_id IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&mut conn)
+        .await?;
+        // The games are then joined from the Mongo database.
+        let fixture_ids: Vec<u32> = leg_rows.iter().map(|leg| leg.fixture_id).collect();
+        let database = Database::acquire_mongo_connection().await?;
+        let games: Vec<Game> = database
+            .collection::<Game>("fixture")
+            .find(doc! {"fixture.id": {"$in": fixture_ids}}, None)
+            .await?
+            .try_collect()
+            .await?;
+        for leg_row in leg_rows {
+            let mut leg: Leg = leg_row.into();
+            leg.game = games
+                .iter()
+                .find(|game| game.fixture.id == leg.fixture_id)
+                .cloned();
+            if let Some(slip) = slips.iter_mut().find(|slip| slip.id == leg.slip_id) {
+                slip.legs.push(leg);
+            }
+        }
+        Ok(slips)
+    }
+
+    /// Push the subquery aggregating the legs of the accumulators having a
+    /// leg on the given fixture.
+    ///
+    /// For each slip, it counts the legs still pending, lost and not voided,
+    /// and computes the product of the odds of the legs that haven't been
+    /// voided.
+    ///
+    /// # Arguments
+    ///
+    /// - query_builder : the query the subquery is pushed into.
+    /// - fixture_id : the fixture whose legs have just been settled.
+    pub(crate) fn push_legs(query_builder: &mut QueryBuilder<'_, MySql>, fixture_id: u32) {
+        query_builder
+            .push(
+                "SELECT slip_id, SUM(outcome IS NULL) AS `pending`, SUM(outcome <= 0 AND NOT is_void) AS `lost`, SUM(NOT is_void) AS `played`, EXP(SUM(IF(is_void, 0, LN(stake)))) AS `odds`
+                FROM USER_BET WHERE slip_id IN (SELECT slip_id FROM USER_BET WHERE fixture_id=",
+            )
+            .push_bind(fixture_id)
+            .push(" AND slip_id IS NOT NULL) GROUP BY slip_id");
+    }
+
+    /// Settle the accumulators having a leg on the given fixture.
+    ///
+    /// An accumulator is only settled once all its legs have been processed.
+    /// It is voided if all the legs are voided. Otherwise it is scored with
+    /// the season's rule as a single pick, right if no leg is lost, whose odd
+    /// is the product of the odds of the legs that haven't been voided.
+    ///
+    /// Returns the number of accumulators updated.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture whose legs have just been settled.
+    /// - scoring_rule : the rule used to score the accumulators.
+    pub(crate) async fn settle_bet_slips(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+        scoring_rule: &ScoringRule,
+    ) -> Result<u64, ApplicationError> {
+        let mut query_builder = QueryBuilder::new("UPDATE BET_SLIP bs INNER JOIN (");
+        Self::push_legs(&mut query_builder, fixture_id);
+        query_builder.push(
+            ") legs ON legs.slip_id = bs.id SET bs.outcome=IF(legs.played = 0, 0, IF(legs.lost = 0",
+        );
+        scoring_rule.push_points(&mut query_builder, "legs.odds");
+        query_builder.push("), bs.is_void=(legs.played = 0) WHERE legs.pending = 0");
+        let update_result = query_builder.build().execute(conn).await?;
+        Ok(update_result.rows_affected())
+    }
+}
@@ -97,18 +97,17 @@ impl Entity {
         ))
     }
 
-    /// Store the serialized value of the model into the mongo database.
+    /// Store the bookmakers into the mongo database.
     ///
     /// # Arguments
     ///
-    /// - value : The serialized value of the struct.
+    /// - models : The bookmakers.
     #[cfg(feature = "cli")]
-    pub async fn store(value: &str) -> Result<(), ApplicationError> {
+    pub async fn store(models: Vec<Model>) -> Result<(), ApplicationError> {
         let database = Database::acquire_mongo_connection().await.unwrap();
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
-        let models: Vec<Model> = serde_json::from_str(value)?;
         for model in models {
             database
                 .collection::<Model>("bookmaker")
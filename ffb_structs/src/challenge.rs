@@ -0,0 +1,497 @@
+//! A challenge is a MySQL structure that represents a duel between two users
+//! over a set of fixtures.
+//!
+//! A user challenges another one on some upcoming games open to bets. Once
+//! the challenge has been accepted, each user bets on these games as usual,
+//! and the challenge is won by the one who earned the most points on them
+//! once all the games have been processed. A challenge left unanswered
+//! expires once its first game has kicked off.
+
+use crate::bet;
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::game::Model as Game;
+use crate::season;
+use crate::transaction_result::TransactionResult;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, MySqlConnection};
+use std::collections::HashSet;
+
+/// The status of a challenge.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum ChallengeStatus {
+    /// The opponent hasn't answered yet.
+    Pending = 1,
+    /// The opponent has accepted the challenge.
+    Accepted = 2,
+    /// The opponent has declined the challenge.
+    Declined = 3,
+    /// All the games have been processed and the points compared.
+    Settled = 4,
+    /// The opponent hasn't answered before the first game kicked off.
+    Expired = 5,
+}
+
+/// A challenge as it is stored within the MySQL database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct Row {
+    pub(crate) id: u32,
+    pub(crate) challenger_id: u32,
+    pub(crate) challenger_name: String,
+    pub(crate) opponent_id: u32,
+    pub(crate) opponent_name: String,
+    pub(crate) status_id: ChallengeStatus,
+    pub(crate) challenger_points: Option<i64>,
+    pub(crate) opponent_points: Option<i64>,
+    pub(crate) winner_id: Option<u32>,
+    pub(crate) created_on: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Model {
+    /// The challenge's id.
+    pub id: u32,
+    /// The user who made the challenge.
+    pub challenger_id: u32,
+    /// The name of the user who made the challenge.
+    pub challenger_name: String,
+    /// The challenged user.
+    pub opponent_id: u32,
+    /// The name of the challenged user.
+    pub opponent_name: String,
+    /// The status of the challenge.
+    pub status_id: ChallengeStatus,
+    /// The points earned by the challenger, none until settled.
+    pub challenger_points: Option<i64>,
+    /// The points earned by the opponent, none until settled.
+    pub opponent_points: Option<i64>,
+    /// The winner of the challenge, none until settled or if it is a draw.
+    pub winner_id: Option<u32>,
+    /// When the challenge has been made, as YYYY-MM-DD HH:MM.
+    pub created_on: String,
+    /// The fixtures the challenge stands on.
+    pub fixture_ids: Vec<u32>,
+    /// The games the challenge stands on.
+    ///
+    /// The fixtures removed from the Mongo database are missing.
+    pub games: Vec<Game>,
+}
+
+impl Model {
+    /// Whether the challenge has been settled as a draw.
+    pub fn is_draw(&self) -> bool {
+        self.status_id == ChallengeStatus::Settled && self.winner_id.is_none()
+    }
+}
+
+impl From<Row> for Model {
+    fn from(row: Row) -> Self {
+        Model {
+            id: row.id,
+            challenger_id: row.challenger_id,
+            challenger_name: row.challenger_name,
+            opponent_id: row.opponent_id,
+            opponent_name: row.opponent_name,
+            status_id: row.status_id,
+            challenger_points: row.challenger_points,
+            opponent_points: row.opponent_points,
+            winner_id: row.winner_id,
+            created_on: row.created_on,
+            fixture_ids: Vec::new(),
+            games: Vec::new(),
+        }
+    }
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Challenge a user over the given fixtures.
+    ///
+    /// Each fixture has to be a bet of the current season that hasn't started
+    /// yet.
+    ///
+    /// Returns the id of the new challenge.
+    ///
+    /// # Arguments
+    ///
+    /// - challenger_id : the id of the user who makes the challenge.
+    /// - opponent_login : the login of the challenged user.
+    /// - fixture_ids : the fixtures the challenge stands on.
+    pub async fn create(
+        challenger_id: u32,
+        opponent_login: &str,
+        fixture_ids: Vec<u32>,
+    ) -> Result<u32, ApplicationError> {
+        let fixture_ids: HashSet<u32> = fixture_ids.into_iter().collect();
+        if fixture_ids.is_empty() {
+            return Err(ApplicationError::EmptyChallenge);
+        }
+        let season_id: u32 = season::Entity::get_current_season_id().await?;
+        let mut conn = Database::acquire_sql_connection().await?;
+        let opponent_id: Option<(u32,)> =
+            sqlx::query_as("SELECT id FROM USER WHERE login=? AND is_authorized=1")
+                .bind(opponent_login)
+                .fetch_optional(&mut conn)
+                .await?;
+        let opponent_id: u32 = match opponent_id {
+            Some((opponent_id,)) if opponent_id != challenger_id => opponent_id,
+            _ => {
+                warn!(
+                    "User {} has tried to challenge the invalid opponent {}",
+                    challenger_id, opponent_login
+                );
+                return Err(ApplicationError::InvalidOpponent(opponent_login.into()));
+            }
+        };
+        let now: DateTime<Utc> = Utc::now();
+        for fixture_id in &fixture_ids {
+            let (_, game) = bet::Entity::validate_open_bet(challenger_id, *fixture_id).await?;
+            if game.fixture.timestamp < now.timestamp() as f64 {
+                return Err(ApplicationError::FormOutdated);
+            }
+        }
+        let mut tx = conn.begin().await?;
+        let challenge_id: u32 = sqlx::query(
+            "INSERT INTO CHALLENGE(challenger_id, opponent_id, season_id, status_id) VALUES(?,?,?,?)",
+        )
+        .bind(challenger_id)
+        .bind(opponent_id)
+        .bind(season_id)
+        .bind(ChallengeStatus::Pending)
+        .execute(&mut tx)
+        .await?
+        .last_insert_id() as u32;
+        for fixture_id in fixture_ids {
+            sqlx::query("INSERT INTO CHALLENGE_FIXTURE(challenge_id, fixture_id) VALUES(?,?)")
+                .bind(challenge_id)
+                .bind(fixture_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        debug!(
+            "The user {} has challenged the user {} with the challenge {}",
+            challenger_id, opponent_id, challenge_id
+        );
+        Ok(challenge_id)
+    }
+
+    /// Answer a pending challenge.
+    ///
+    /// A challenge can only be accepted as long as none of its games has
+    /// started.
+    ///
+    /// # Arguments
+    ///
+    /// - opponent_id : the id of the challenged user.
+    /// - challenge_id : the challenge to answer.
+    /// - accept : whether the challenge is accepted or declined.
+    pub async fn answer(
+        opponent_id: u32,
+        challenge_id: u32,
+        accept: bool,
+    ) -> Result<TransactionResult, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        if accept {
+            let fixture_ids: Vec<u32> = Self::get_fixture_ids(&mut conn, challenge_id).await?;
+            let now: DateTime<Utc> = Utc::now();
+            let database = Database::acquire_mongo_connection().await?;
+            let number_of_started_games: u64 = database
+                .collection::<Game>("fixture")
+                .count_documents(
+                    doc! {
+                        "fixture.id": {"$in": fixture_ids},
+                        "fixture.timestamp": {"$lt": now.timestamp()}
+                    },
+                    None,
+                )
+                .await?;
+            if number_of_started_games != 0 {
+                warn!(
+                    "User {} has tried to accept the challenge {} after the kickoff",
+                    opponent_id, challenge_id
+                );
+                return Err(ApplicationError::FormOutdated);
+            }
+        }
+        let status: ChallengeStatus = match accept {
+            true => ChallengeStatus::Accepted,
+            false => ChallengeStatus::Declined,
+        };
+        let result = sqlx::query(
+            "UPDATE CHALLENGE SET status_id=? WHERE id=? AND opponent_id=? AND status_id=?",
+        )
+        .bind(status)
+        .bind(challenge_id)
+        .bind(opponent_id)
+        .bind(ChallengeStatus::Pending)
+        .execute(&mut conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ApplicationError::ChallengeNotFound(challenge_id));
+        }
+        debug!(
+            "The user {} has answered {} to the challenge {}",
+            opponent_id, status, challenge_id
+        );
+        Ok(TransactionResult::expect_single_result(
+            result.rows_affected(),
+        ))
+    }
+
+    /// Get the challenges a user takes part in, the most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub async fn get_user_challenges(user_id: u32) -> Result<Vec<Model>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let rows: Vec<Row> = sqlx::query_as(
+            "SELECT chl.id, chl.challenger_id, chr.name AS `challenger_name`, chl.opponent_id, opp.name AS `opponent_name`, chl.status_id, chl.challenger_points, chl.opponent_points, chl.winner_id, DATE_FORMAT(chl.datetime, '%Y-%m-%d %H:%i') AS `created_on`
+            FROM CHALLENGE chl INNER JOIN USER chr ON chr.id = chl.challenger_id INNER JOIN USER opp ON opp.id = chl.opponent_id
+            WHERE chl.challenger_id=? OR chl.opponent_id=?
+            ORDER BY chl.datetime DESC, chl.id DESC",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&mut conn)
+        .await?;
+        let mut challenges: Vec<Model> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut challenge: Model = row.into();
+            challenge.fixture_ids = Self::get_fixture_ids(&mut conn, challenge.id).await?;
+            challenges.push(challenge);
+        }
+        // The games are then joined from the Mongo database.
+        let fixture_ids: Vec<u32> = challenges
+            .iter()
+            .flat_map(|challenge| challenge.fixture_ids.clone())
+            .collect();
+        let database = Database::acquire_mongo_connection().await?;
+        let games: Vec<Game> = database
+            .collection::<Game>("fixture")
+            .find(doc! {"fixture.id": {"$in": fixture_ids}}, None)
+            .await?
+            .try_collect()
+            .await?;
+        for challenge in challenges.iter_mut() {
+            challenge.games = games
+                .iter()
+                .filter(|game| challenge.fixture_ids.contains(&game.fixture.id))
+                .cloned()
+                .collect();
+        }
+        Ok(challenges)
+    }
+
+    /// Expire the challenges still pending once their first game has kicked
+    /// off, since they can't be accepted anymore.
+    ///
+    /// Returns the number of challenges expired.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    pub(crate) async fn expire_challenges(
+        conn: &mut MySqlConnection,
+    ) -> Result<u64, ApplicationError> {
+        let challenge_ids: Vec<(u32,)> =
+            sqlx::query_as("SELECT id FROM CHALLENGE WHERE status_id=?")
+                .bind(ChallengeStatus::Pending)
+                .fetch_all(&mut *conn)
+                .await?;
+        let now: DateTime<Utc> = Utc::now();
+        let database = Database::acquire_mongo_connection().await?;
+        let mut number_of_challenges_expired: u64 = 0;
+        for (challenge_id,) in challenge_ids {
+            let fixture_ids: Vec<u32> = Self::get_fixture_ids(&mut *conn, challenge_id).await?;
+            let number_of_started_games: u64 = database
+                .collection::<Game>("fixture")
+                .count_documents(
+                    doc! {
+                        "fixture.id": {"$in": fixture_ids},
+                        "fixture.timestamp": {"$lt": now.timestamp()}
+                    },
+                    None,
+                )
+                .await?;
+            if number_of_started_games == 0 {
+                continue;
+            }
+            number_of_challenges_expired +=
+                sqlx::query("UPDATE CHALLENGE SET status_id=? WHERE id=? AND status_id=?")
+                    .bind(ChallengeStatus::Expired)
+                    .bind(challenge_id)
+                    .bind(ChallengeStatus::Pending)
+                    .execute(&mut *conn)
+                    .await?
+                    .rows_affected();
+            info!("Challenge {} has expired without an answer", challenge_id);
+        }
+        Ok(number_of_challenges_expired)
+    }
+
+    /// Settle the accepted challenges whose games have all been processed.
+    ///
+    /// The points of each user are the sum of the outcomes of the bets, score
+    /// predictions and market bets made on the challenge's games.
+    ///
+    /// Returns the number of challenges settled.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    pub(crate) async fn settle_challenges(
+        conn: &mut MySqlConnection,
+    ) -> Result<u64, ApplicationError> {
+        let challenges: Vec<(u32, u32, u32)> = sqlx::query_as(
+            "SELECT id, challenger_id, opponent_id FROM CHALLENGE WHERE status_id=?",
+        )
+        .bind(ChallengeStatus::Accepted)
+        .fetch_all(&mut *conn)
+        .await?;
+        let mut number_of_challenges_settled: u64 = 0;
+        for (challenge_id, challenger_id, opponent_id) in challenges {
+            if Self::settle_challenge(&mut *conn, challenge_id, challenger_id, opponent_id).await? {
+                number_of_challenges_settled += 1;
+            }
+        }
+        Ok(number_of_challenges_settled)
+    }
+
+    /// Settle again the settled challenges standing on a fixture whose bets
+    /// have just been settled again.
+    ///
+    /// Returns the number of challenges settled again.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture settled again.
+    pub(crate) async fn resettle_challenges(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+    ) -> Result<u64, ApplicationError> {
+        let challenges: Vec<(u32, u32, u32)> = sqlx::query_as(
+            "SELECT id, challenger_id, opponent_id FROM CHALLENGE WHERE status_id=? AND id IN (SELECT challenge_id FROM CHALLENGE_FIXTURE WHERE fixture_id=?)",
+        )
+        .bind(ChallengeStatus::Settled)
+        .bind(fixture_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let mut number_of_challenges_settled: u64 = 0;
+        for (challenge_id, challenger_id, opponent_id) in challenges {
+            if Self::settle_challenge(&mut *conn, challenge_id, challenger_id, opponent_id).await? {
+                number_of_challenges_settled += 1;
+            }
+        }
+        Ok(number_of_challenges_settled)
+    }
+
+    /// Settle a challenge if all its games have been processed.
+    ///
+    /// A game missing from the Mongo database isn't considered as processed,
+    /// so that the challenge isn't settled on part of its games.
+    ///
+    /// Returns whether the challenge has been settled.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - challenge_id : the MySQL challenge id.
+    /// - challenger_id : the id of the user who made the challenge.
+    /// - opponent_id : the id of the challenged user.
+    async fn settle_challenge(
+        conn: &mut MySqlConnection,
+        challenge_id: u32,
+        challenger_id: u32,
+        opponent_id: u32,
+    ) -> Result<bool, ApplicationError> {
+        let fixture_ids: Vec<u32> = Self::get_fixture_ids(&mut *conn, challenge_id).await?;
+        let database = Database::acquire_mongo_connection().await?;
+        let number_of_processed_games: u64 = database
+            .collection::<Game>("fixture")
+            .count_documents(
+                doc! {
+                    "fixture.id": {"$in": &fixture_ids},
+                    "processedAs": {"$ne": null}
+                },
+                None,
+            )
+            .await?;
+        if number_of_processed_games != fixture_ids.len() as u64 {
+            return Ok(false);
+        }
+        let challenger_points: i64 =
+            Self::get_points(&mut *conn, challenge_id, challenger_id).await?;
+        let opponent_points: i64 = Self::get_points(&mut *conn, challenge_id, opponent_id).await?;
+        let winner_id: Option<u32> = match challenger_points.cmp(&opponent_points) {
+            std::cmp::Ordering::Greater => Some(challenger_id),
+            std::cmp::Ordering::Less => Some(opponent_id),
+            std::cmp::Ordering::Equal => None,
+        };
+        sqlx::query("UPDATE CHALLENGE SET status_id=?, challenger_points=?, opponent_points=?, winner_id=? WHERE id=?")
+            .bind(ChallengeStatus::Settled)
+            .bind(challenger_points)
+            .bind(opponent_points)
+            .bind(winner_id)
+            .bind(challenge_id)
+            .execute(conn)
+            .await?;
+        info!(
+            "Challenge {} has been settled with {} points against {}",
+            challenge_id, challenger_points, opponent_points
+        );
+        Ok(true)
+    }
+
+    /// Get the points earned by a user on the games of a challenge.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - challenge_id : the MySQL challenge id.
+    /// - user_id : the MySQL user id.
+    async fn get_points(
+        conn: &mut MySqlConnection,
+        challenge_id: u32,
+        user_id: u32,
+    ) -> Result<i64, ApplicationError> {
+        let (points,): (i64,) = sqlx::query_as(
+            "SELECT CAST(IFNULL(SUM(outcome), 0) AS SIGNED)
+            FROM (SELECT user_id, fixture_id, outcome FROM USER_BET WHERE slip_id IS NULL UNION ALL SELECT user_id, fixture_id, outcome FROM USER_SCORE_BET UNION ALL SELECT user_id, fixture_id, outcome FROM USER_MARKET_BET) ub
+            WHERE ub.user_id=? AND ub.fixture_id IN (SELECT fixture_id FROM CHALLENGE_FIXTURE WHERE challenge_id=?)",
+        )
+        .bind(user_id)
+        .bind(challenge_id)
+        .fetch_one(conn)
+        .await?;
+        Ok(points)
+    }
+
+    /// Get the fixtures a challenge stands on.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - challenge_id : the MySQL challenge id.
+    async fn get_fixture_ids(
+        conn: &mut MySqlConnection,
+        challenge_id: u32,
+    ) -> Result<Vec<u32>, ApplicationError> {
+        let fixture_ids: Vec<(u32,)> =
+            sqlx::query_as("SELECT fixture_id FROM CHALLENGE_FIXTURE WHERE challenge_id=?")
+                .bind(challenge_id)
+                .fetch_all(conn)
+                .await?;
+        Ok(fixture_ids
+            .into_iter()
+            .map(|(fixture_id,)| fixture_id)
+            .collect())
+    }
+}
@@ -136,7 +136,7 @@ pub struct Teams {
 }
 
 /// The goals associed to a game.
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
 pub struct Goals {
     /// The home team's goals.
     pub home: Option<i16>,
@@ -144,6 +144,16 @@ pub struct Goals {
     pub away: Option<i16>,
 }
 
+impl Goals {
+    /// The goals as a (home, away) pair, given both are known.
+    pub fn as_pair(&self) -> Option<(i16, i16)> {
+        match (self.home, self.away) {
+            (Some(home), Some(away)) => Some((home, away)),
+            _ => None,
+        }
+    }
+}
+
 /// The score of a game.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Score {
@@ -183,6 +193,34 @@ pub struct Bet {
 pub struct Better {
     pub user_id: u32,
     pub game_result: crate::bet::GameResult,
+    /// The amount of the user's balance put on the bet.
+    #[serde(default)]
+    pub amount: Option<u32>,
+    /// Whether the user's joker is attached to the bet.
+    #[serde(default)]
+    pub joker: bool,
+}
+
+/// A user's prediction of the exact score of a game.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Hash, PartialEq, Eq)]
+pub struct ScoreBetter {
+    /// The MySQL user ID.
+    pub user_id: u32,
+    /// The predicted home team's goals.
+    pub home: u8,
+    /// The predicted away team's goals.
+    pub away: u8,
+}
+
+/// A user's pick on one of the secondary markets of a game.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Hash, PartialEq, Eq)]
+pub struct MarketBetter {
+    /// The MySQL user ID.
+    pub user_id: u32,
+    /// The market the pick stands on.
+    pub market: crate::market_bet::Market,
+    /// The user's pick.
+    pub pick: crate::market_bet::MarketPick,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -191,3 +229,60 @@ pub struct Odds {
     pub draw: f32,
     pub away: f32,
 }
+
+impl Odds {
+    /// Returns the odd that has been stored for the given result.
+    ///
+    /// # Arguments
+    ///
+    /// - game_result : the result to get the odd for.
+    pub fn get_odd_for_result(&self, game_result: &crate::bet::GameResult) -> f32 {
+        match game_result {
+            crate::bet::GameResult::Win => self.home,
+            crate::bet::GameResult::Draw => self.draw,
+            crate::bet::GameResult::Loss => self.away,
+        }
+    }
+}
+
+/// The odds of the secondary markets of a game.
+///
+/// Each odd is optional as the bookmakers don't always provide all the
+/// markets.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct MarketOdds {
+    pub over: Option<f32>,
+    pub under: Option<f32>,
+    pub both_teams_score: Option<f32>,
+    pub not_both_teams_score: Option<f32>,
+    pub home_or_draw: Option<f32>,
+    pub home_or_away: Option<f32>,
+    pub draw_or_away: Option<f32>,
+}
+
+impl MarketOdds {
+    /// Returns the odd that has been stored for the given pick, if any.
+    ///
+    /// # Arguments
+    ///
+    /// - pick : the pick to get the odd for.
+    pub fn get_odd_for_pick(&self, pick: &crate::market_bet::MarketPick) -> Option<f32> {
+        match pick {
+            crate::market_bet::MarketPick::Over => self.over,
+            crate::market_bet::MarketPick::Under => self.under,
+            crate::market_bet::MarketPick::BothTeamsScore => self.both_teams_score,
+            crate::market_bet::MarketPick::NotBothTeamsScore => self.not_both_teams_score,
+            crate::market_bet::MarketPick::HomeOrDraw => self.home_or_draw,
+            crate::market_bet::MarketPick::HomeOrAway => self.home_or_away,
+            crate::market_bet::MarketPick::DrawOrAway => self.draw_or_away,
+        }
+    }
+
+    /// Returns the picks that can be bet on, with their odds.
+    pub fn get_available_picks(&self) -> Vec<(crate::market_bet::MarketPick, f32)> {
+        crate::market_bet::MarketPick::ALL
+            .into_iter()
+            .filter_map(|pick| self.get_odd_for_pick(&pick).map(|odd| (pick, odd)))
+            .collect()
+    }
+}
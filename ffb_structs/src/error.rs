@@ -34,6 +34,30 @@ pub enum ApplicationError {
     ParseError(String),
     /// When a form is outdated, and a request has been submitted by a user since.
     FormOutdated,
+    /// When the requested game doesn't exist.
+    GameNotFound(u32),
+    /// When a user tries to bet on a game that hasn't been added to the bets.
+    GameNotABet(u32),
+    /// When a user tries to bet on a game that doesn't have any odds stored.
+    NoOddsForGame(u32),
+    /// When a bet is requested for a season that is closed.
+    SeasonClosed(u32),
+    /// When an accumulator is requested with less legs than required.
+    NotEnoughLegs(usize),
+    /// When a user tries to bet on a game he has already bet on.
+    AlreadyBet(u32),
+    /// When a user tries to bet more than his balance.
+    InsufficientBalance(u32),
+    /// When a user tries to join a group with an unknown invite code.
+    InvalidInviteCode(String),
+    /// When a user challenges an unknown user or himself.
+    InvalidOpponent(String),
+    /// When a challenge is made without any game.
+    EmptyChallenge,
+    /// When the requested challenge doesn't exist or can't be answered.
+    ChallengeNotFound(u32),
+    /// When a user has already used the joker of the period on another game.
+    JokerAlreadyUsed(u32),
 }
 
 impl ApplicationError {
@@ -41,6 +65,17 @@ impl ApplicationError {
     pub fn http_error_code(&self) -> u16 {
         match *self {
             Self::FormOutdated => 205,
+            Self::GameNotABet(_)
+            | Self::NoOddsForGame(_)
+            | Self::SeasonClosed(_)
+            | Self::NotEnoughLegs(_)
+            | Self::AlreadyBet(_)
+            | Self::InsufficientBalance(_)
+            | Self::InvalidInviteCode(_)
+            | Self::InvalidOpponent(_)
+            | Self::EmptyChallenge
+            | Self::JokerAlreadyUsed(_) => 400,
+            Self::GameNotFound(_) | Self::ChallengeNotFound(_) => 404,
             _ => 500,
         }
     }
@@ -58,7 +93,19 @@ impl fmt::Display for ApplicationError {
             Self::SerialError => "A serial error happened".into(),
             Self::NoTokenStored => "There are no tokens stored to call the remote API endpoint".into(),
             Self::ParseError(err)=> format!("A parse error happened : {}", err),
-            Self::FormOutdated => "The request that has been submitted is most likely using expired parameters and is thus not valid, please refresh your browser".into()
+            Self::FormOutdated => "The request that has been submitted is most likely using expired parameters and is thus not valid, please refresh your browser".into(),
+            Self::GameNotFound(fixture_id) => format!("The game {} couldn't have been found", fixture_id),
+            Self::GameNotABet(fixture_id) => format!("The game {} isn't open to bets", fixture_id),
+            Self::NoOddsForGame(fixture_id) => format!("No odds are available yet for the game {}", fixture_id),
+            Self::SeasonClosed(season_id) => format!("The season {} is closed, no more bets can be made on it", season_id),
+            Self::NotEnoughLegs(number_of_legs) => format!("An accumulator needs at least {} games, {} have been given", crate::bet_slip::MIN_LEGS, number_of_legs),
+            Self::AlreadyBet(fixture_id) => format!("A bet has already been made on the game {}", fixture_id),
+            Self::InsufficientBalance(amount) => format!("The balance isn't sufficient to bet {}", amount),
+            Self::InvalidInviteCode(invite_code) => format!("No group matches the invite code {}", invite_code),
+            Self::InvalidOpponent(login) => format!("The user {} can't be challenged", login),
+            Self::EmptyChallenge => "A challenge needs at least one game".into(),
+            Self::ChallengeNotFound(challenge_id) => format!("The challenge {} couldn't have been found or has already been answered", challenge_id),
+            Self::JokerAlreadyUsed(fixture_id) => format!("The joker of this period has already been used on the game {}", fixture_id),
         };
         write!(f, "{}", reason)
     }
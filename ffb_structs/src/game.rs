@@ -11,26 +11,68 @@
 //! * A bet is made by a user.
 //! * The game is refreshed.
 //! * Odds are fetched.
+//!
+//! The users' picks are kept hidden until the kickoff, the split of the picks
+//! is then computed along with the games and cached with them.
 
-use crate::bet::GameResult;
-#[cfg(feature = "server")]
+use crate::bet::{GameResult, Settlement};
 use crate::common_api_structs::ShortStatus;
-use crate::common_api_structs::{Better, Fixture, Goals, Odds, Score, Teams};
+use crate::common_api_structs::{
+    Better, Fixture, Goals, MarketBetter, MarketOdds, Odds, Score, ScoreBetter, Teams,
+};
 use crate::database::Database;
 use crate::error::ApplicationError;
 use crate::league::Model as League;
 #[cfg(feature = "server")]
+use crate::market_bet::MarketPick;
+use crate::season::SettlementPolicy;
+#[cfg(feature = "server")]
 use crate::transaction_result::TransactionResult;
 use bson::oid::ObjectId;
 #[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 #[cfg(feature = "server")]
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "server")]
+use sqlx::{MySql, QueryBuilder, Row};
+#[cfg(feature = "server")]
+use std::collections::HashMap;
 use std::collections::HashSet;
 #[cfg(feature = "server")]
 use std::hash::{Hash, Hasher};
 
+/// The share of the bets made on one of the results of a game.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct CrowdPick {
+    /// The picked result.
+    pub game_result: GameResult,
+    /// The share of the bets made on the result, in percent.
+    pub percentage: u32,
+    /// The users who picked the result, as their MySQL user ID and name.
+    pub betters: Vec<(u32, String)>,
+}
+
+#[cfg(feature = "server")]
+impl CrowdPick {
+    /// Get the names of the given friends who picked the result, separated
+    /// by commas.
+    ///
+    /// # Arguments
+    ///
+    /// - friend_ids : the MySQL user IDs of the friends.
+    pub fn get_friend_names(&self, friend_ids: &HashSet<u32>) -> String {
+        self.betters
+            .iter()
+            .filter(|(user_id, _)| friend_ids.contains(user_id))
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Model {
@@ -52,17 +94,48 @@ pub struct Model {
     pub season_id: Option<u32>,
     /// Informs if the game given it is a bet has been processed.
     ///
-    /// A processed game is a game whose bets have already been validated.
+    /// A processed game is a game whose bets have already been validated, or
+    /// voided if the game didn't reach a result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processed_as: Option<Settlement>,
+    /// The score at the fulltime the game has been processed with.
+    ///
+    /// Used to detect the scores that have been corrected after the game has
+    /// been processed.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub processed_as: Option<GameResult>,
+    pub processed_score: Option<Goals>,
     /// The odds associed to the bets.
     ///
     /// They should contain the odds for home, draw and away team winning.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub odds: Option<Odds>,
+    /// The odds of the first snapshot taken for the game.
+    ///
+    /// Used to show how the odds moved since they have been published.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opening_odds: Option<Odds>,
+    /// The id of the odds snapshot the current odds come from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub odds_snapshot_id: Option<ObjectId>,
+    /// The odds of the secondary markets (goals, both teams to score and
+    /// double chance).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_odds: Option<MarketOdds>,
     /// The list of users who have bet on the game.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub betters: Option<HashSet<Better>>,
+    /// The split of the bets made on the game, for each result.
+    ///
+    /// Isn't stored, only computed once the game has started so that nobody
+    /// can copy the others' picks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crowd_picks: Option<Vec<CrowdPick>>,
+    /// The list of users who have predicted the game's exact score.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_betters: Option<HashSet<ScoreBetter>>,
+    /// The list of users who have bet on the secondary markets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_betters: Option<HashSet<MarketBetter>>,
     /// The local league logo.
     ///
     /// Must correspond to a local asset.
@@ -95,9 +168,12 @@ pub struct Model {
 
 #[cfg(feature = "server")]
 impl Model {
-    /// Whether the game is started or not.
+    /// Whether the game is started or not, so that it can't be bet on
+    /// anymore.
     ///
-    /// If the game has been cancelled or postponed, it will return false.
+    /// If the game has been cancelled or postponed, it will return true as
+    /// well, see [Model::has_kicked_off] for the games that have actually
+    /// been played.
     pub fn is_started(&self) -> bool {
         !matches!(
             self.fixture.status.short,
@@ -105,6 +181,28 @@ impl Model {
         )
     }
 
+    /// Whether the game is being played or has been played.
+    ///
+    /// If the game has been cancelled, postponed, abandoned or awarded, it
+    /// will return false.
+    pub fn has_kicked_off(&self) -> bool {
+        matches!(
+            self.fixture.status.short,
+            ShortStatus::Fh
+                | ShortStatus::Ht
+                | ShortStatus::Sh
+                | ShortStatus::Et
+                | ShortStatus::Bt
+                | ShortStatus::P
+                | ShortStatus::Susp
+                | ShortStatus::Int
+                | ShortStatus::Live
+                | ShortStatus::Ft
+                | ShortStatus::Aet
+                | ShortStatus::Pen
+        )
+    }
+
     /// Whether the game is finished or not.
     ///
     /// If the game has been cancelled or postponed, it will return false.
@@ -132,8 +230,151 @@ impl Model {
             None
         }
     }
+
+    /// Get the amount bet by the given user id.
+    ///
+    /// # Argument
+    ///
+    /// - user_id : The MySQL user ID.
+    pub fn get_bet_amount_for_user(&self, user_id: &u32) -> Option<u32> {
+        self.betters.as_ref().and_then(|betters| {
+            betters
+                .iter()
+                .find(|bet| &bet.user_id == user_id)
+                .and_then(|bet| bet.amount)
+        })
+    }
+
+    /// Whether the given user id has attached his joker to his bet.
+    ///
+    /// # Argument
+    ///
+    /// - user_id : The MySQL user ID.
+    pub fn has_joker_for_user(&self, user_id: &u32) -> bool {
+        self.betters.as_ref().is_some_and(|betters| {
+            betters
+                .iter()
+                .any(|bet| &bet.user_id == user_id && bet.joker)
+        })
+    }
+
+    /// Get the exact score predicted by the given user id.
+    ///
+    /// # Argument
+    ///
+    /// - user_id : The MySQL user ID.
+    pub fn get_score_bet_for_user(&self, user_id: &u32) -> Option<ScoreBetter> {
+        self.score_betters
+            .as_ref()
+            .and_then(|score_betters| {
+                score_betters
+                    .iter()
+                    .find(|score_bet| &score_bet.user_id == user_id)
+            })
+            .cloned()
+    }
+
+    /// Get the odd movement on the given result since the first snapshot.
+    ///
+    /// Returns none if the odds haven't moved or aren't known.
+    ///
+    /// # Argument
+    ///
+    /// - game_result : The result to get the movement of.
+    pub fn get_odd_movement(&self, game_result: GameResult) -> Option<f32> {
+        match (&self.odds, &self.opening_odds) {
+            (Some(odds), Some(opening_odds)) => {
+                let movement: f32 = odds.get_odd_for_result(&game_result)
+                    - opening_odds.get_odd_for_result(&game_result);
+                Some(movement).filter(|movement| 0.005 < movement.abs())
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the given user id has made the given pick.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : The MySQL user ID.
+    /// - pick : The market pick.
+    pub fn has_market_pick(&self, user_id: &u32, pick: &MarketPick) -> bool {
+        self.market_betters
+            .as_ref()
+            .map(|market_betters| {
+                market_betters
+                    .iter()
+                    .any(|market_bet| &market_bet.user_id == user_id && &market_bet.pick == pick)
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl Model {
+    /// Get the team that advanced as a result.
+    ///
+    /// Returns none if the remote API didn't indicate any winner.
+    fn get_advancing_result(&self) -> Option<GameResult> {
+        match (self.teams.home.winner, self.teams.away.winner) {
+            (Some(true), _) => Some(GameResult::Win),
+            (_, Some(true)) => Some(GameResult::Loss),
+            _ => None,
+        }
+    }
+
+    /// Get the settlement of the game's bets.
+    ///
+    /// The game is voided if it has been postponed, cancelled or abandoned,
+    /// or if it has been awarded without a known score. Otherwise it is
+    /// settled with its score at the fulltime, given the game is over.
+    ///
+    /// If the season settles on the team that advances, the games decided
+    /// after extra time or penalties are settled on their winner instead.
+    ///
+    /// # Arguments
+    ///
+    /// - settlement_policy : the policy of the game's season.
+    pub fn get_settlement(&self, settlement_policy: &SettlementPolicy) -> Option<Settlement> {
+        let fulltime_result: Option<GameResult> = match self.score.fulltime {
+            Some(Goals {
+                home: Some(home),
+                away: Some(away),
+            }) => Some(match home - away {
+                v if 0 < v => GameResult::Win,
+                v if v < 0 => GameResult::Loss,
+                _ => GameResult::Draw,
+            }),
+            _ => None,
+        };
+        let result: Option<GameResult> = match (settlement_policy, &self.fixture.status.short) {
+            (SettlementPolicy::Advancing, ShortStatus::Aet | ShortStatus::Pen) => {
+                self.get_advancing_result().or(fulltime_result)
+            }
+            _ => fulltime_result,
+        };
+        match (&self.fixture.status.short, result) {
+            (ShortStatus::Pst | ShortStatus::Canc | ShortStatus::Abd, _) => {
+                Some(Settlement::Void)
+            }
+            (ShortStatus::Awd | ShortStatus::Wo, None) => Some(Settlement::Void),
+            (
+                ShortStatus::Ft
+                | ShortStatus::Aet
+                | ShortStatus::Pen
+                | ShortStatus::Awd
+                | ShortStatus::Wo,
+                Some(result),
+            ) => Some(result.into()),
+            _ => None,
+        }
+    }
 }
 
+/// The time in seconds after the kickoff during which a game that isn't
+/// refreshed yet is considered as being played.
+#[cfg(feature = "cli")]
+const KICKOFF_WINDOW: i64 = 3 * 3600;
+
 pub struct Entity;
 
 impl Entity {
@@ -142,12 +383,10 @@ impl Entity {
     /// # Arguments :
     ///
     /// - date : the date of the game.
-    /// - value : the struct serialized.
+    /// - models : the games played on the date.
     #[cfg(feature = "cli")]
-    pub async fn store(date: &str, value: &str) -> Result<(), ApplicationError> {
+    pub async fn store(date: &str, models: Vec<Model>) -> Result<(), ApplicationError> {
         let database = Database::acquire_mongo_connection().await?;
-        let models: Vec<Model> = serde_json::from_str(value)?;
-        debug!("Games have been serialized with success");
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
@@ -173,6 +412,91 @@ impl Entity {
         Ok(())
     }
 
+    /// Whether some of the stored games are being played.
+    ///
+    /// A game is being played if its status is the one of an ongoing game,
+    /// or if it has kicked off within the last [KICKOFF_WINDOW] seconds while
+    /// its stored status hasn't been refreshed yet.
+    #[cfg(feature = "cli")]
+    pub async fn has_live_games() -> Result<bool, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let mut live_statuses: Vec<bson::Bson> = Vec::new();
+        for status in [
+            ShortStatus::Fh,
+            ShortStatus::Ht,
+            ShortStatus::Sh,
+            ShortStatus::Et,
+            ShortStatus::Bt,
+            ShortStatus::P,
+            ShortStatus::Susp,
+            ShortStatus::Int,
+            ShortStatus::Live,
+        ] {
+            live_statuses.push(bson::to_bson(&status)?);
+        }
+        let now: i64 = chrono::Utc::now().timestamp();
+        let count: u64 = database
+            .collection::<Model>("fixture")
+            .count_documents(
+                doc! {"$or": [
+                    {"fixture.status.short": {"$in": live_statuses}},
+                    {
+                        "fixture.status.short": bson::to_bson(&ShortStatus::Ns)?,
+                        "fixture.timestamp": {"$gte": now - KICKOFF_WINDOW, "$lte": now}
+                    }
+                ]},
+                None,
+            )
+            .await?;
+        Ok(0 < count)
+    }
+
+    /// Get the fixture ids of the games of a league that haven't started yet
+    /// on the given day.
+    ///
+    /// # Arguments
+    ///
+    /// - date : the day of the games.
+    /// - league_id : the remote API id of the league.
+    #[cfg(feature = "cli")]
+    pub async fn get_upcoming_fixture_ids(
+        date: chrono::NaiveDate,
+        league_id: u32,
+    ) -> Result<Vec<u32>, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let fixture_ids = database
+            .collection::<Model>("fixture")
+            .distinct(
+                "fixture.id",
+                doc! {
+                    "fixture.date": {"$regex": format!("^{}", date)},
+                    "league.id": league_id,
+                    "fixture.status.short": bson::to_bson(&ShortStatus::Ns)?
+                },
+                None,
+            )
+            .await?;
+        Ok(fixture_ids
+            .into_iter()
+            .filter_map(|id| id.as_i64().or_else(|| id.as_i32().map(i64::from)))
+            .map(|id| id as u32)
+            .collect())
+    }
+
+    /// Find a game by its fixture id.
+    ///
+    /// # Arguments
+    ///
+    /// - fixture_id : the remote API id of the fixture.
+    pub async fn find_by_fixture_id(fixture_id: u32) -> Result<Option<Model>, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let model: Option<Model> = database
+            .collection::<Model>("fixture")
+            .find_one(doc! {"fixture.id": fixture_id}, None)
+            .await?;
+        Ok(model)
+    }
+
     /// Indicates that the game is now a bet.
     ///
     /// When the game is a bet, the user can do bets on it.
@@ -206,6 +530,31 @@ impl Entity {
         ))
     }
 
+    /// Get the games open to bets that haven't started yet, the earliest
+    /// first.
+    #[cfg(feature = "server")]
+    pub async fn get_open_bets() -> Result<Vec<Model>, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let options: mongodb::options::FindOptions = mongodb::options::FindOptions::builder()
+            .sort(doc! {"fixture.timestamp":1})
+            .build();
+        let now: DateTime<Utc> = Utc::now();
+        let models: Vec<Model> = database
+            .collection::<Model>("fixture")
+            .find(
+                doc! {
+                    "seasonId": {"$ne": null},
+                    "odds": {"$ne": null},
+                    "fixture.timestamp": {"$gte": now.timestamp()}
+                },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?;
+        Ok(models)
+    }
+
     /// Get the last time the games have been fetched for the given date.
     ///
     /// # Arguments
@@ -231,6 +580,71 @@ impl Entity {
         debug!("Games cache has been cleared successfully");
         Ok(())
     }
+
+    /// Compute the split of the picks of the games that have kicked off.
+    ///
+    /// The games that haven't kicked off yet, or never will, are left
+    /// untouched.
+    ///
+    /// # Arguments
+    ///
+    /// - games : the games to compute the split of.
+    #[cfg(feature = "server")]
+    async fn reveal_crowd_picks(games: &mut [Model]) -> Result<(), ApplicationError> {
+        let user_ids: HashSet<u32> = games
+            .iter()
+            .filter(|game| game.has_kicked_off())
+            .filter_map(|game| game.betters.as_ref())
+            .flat_map(|betters| betters.iter().map(|better| better.user_id))
+            .collect();
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut query_builder: QueryBuilder<MySql> =
+            QueryBuilder::new("SELECT id, name FROM USER WHERE id IN (");
+        let mut separated = query_builder.separated(",");
+        for user_id in &user_ids {
+            separated.push_bind(user_id);
+        }
+        separated.push_unseparated(")");
+        let mut names: HashMap<u32, String> = HashMap::with_capacity(user_ids.len());
+        for row in query_builder.build().fetch_all(&mut conn).await? {
+            names.insert(row.try_get("id")?, row.try_get("name")?);
+        }
+        for game in games.iter_mut().filter(|game| game.has_kicked_off()) {
+            let betters: &HashSet<Better> = match &game.betters {
+                Some(betters) if !betters.is_empty() => betters,
+                _ => continue,
+            };
+            let crowd_picks: Vec<CrowdPick> = [GameResult::Win, GameResult::Draw, GameResult::Loss]
+                .into_iter()
+                .map(|game_result| {
+                    let mut result_betters: Vec<(u32, String)> = betters
+                        .iter()
+                        .filter(|better| better.game_result == game_result)
+                        .filter_map(|better| {
+                            names
+                                .get(&better.user_id)
+                                .map(|name| (better.user_id, name.clone()))
+                        })
+                        .collect();
+                    result_betters.sort_by(|(_, name), (_, other_name)| name.cmp(other_name));
+                    let number_of_picks: usize = betters
+                        .iter()
+                        .filter(|better| better.game_result == game_result)
+                        .count();
+                    CrowdPick {
+                        game_result,
+                        percentage: (number_of_picks * 100 / betters.len()) as u32,
+                        betters: result_betters,
+                    }
+                })
+                .collect();
+            game.crowd_picks = Some(crowd_picks);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(feature = "server")]
@@ -357,12 +771,13 @@ impl EntityBuilder {
             if !query_selector.is_empty() {
                 key.insert("$or", query_selector);
             }
-            let model: Vec<Model> = database
+            let mut model: Vec<Model> = database
                 .collection::<Model>("fixture")
                 .find(key, options)
                 .await?
                 .try_collect()
                 .await?;
+            Entity::reveal_crowd_picks(&mut model).await?;
             redis::cmd("SET")
                 .arg(redis_key.as_str())
                 .arg(serde_json::to_string(&model)?)
@@ -374,3 +789,103 @@ impl EntityBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a game from its status, its fulltime score and the team that
+    /// advanced, if any.
+    fn build_game(status: &str, fulltime: Option<(i16, i16)>, home_winner: Option<bool>) -> Model {
+        let fulltime = fulltime.map(|(home, away)| serde_json::json!({"home": home, "away": away}));
+        let away_winner: Option<bool> = home_winner.map(|winner| !winner);
+        serde_json::from_value(serde_json::json!({
+            "fixture": {
+                "id": 1,
+                "timestamp": 1_650_000_000.0,
+                "date": "2022-04-15T19:00:00Z",
+                "venue": {"id": null, "name": null, "city": null},
+                "status": {"long": "", "short": status, "elapsed": null}
+            },
+            "league": {"id": 2, "name": "League", "country": null, "logo": "", "flag": null},
+            "teams": {
+                "home": {"id": 3, "name": "Home", "logo": "", "winner": home_winner},
+                "away": {"id": 4, "name": "Away", "logo": "", "winner": away_winner}
+            },
+            "goals": {"home": null, "away": null},
+            "score": {"fulltime": fulltime, "extratime": null, "penalty": null}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn finished_game_is_settled_with_its_fulltime_result() {
+        let game = build_game("FT", Some((2, 1)), Some(true));
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::RegularTime),
+            Some(Settlement::Win)
+        );
+        let game = build_game("FT", Some((0, 0)), None);
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::Advancing),
+            Some(Settlement::Draw)
+        );
+    }
+
+    #[test]
+    fn game_decided_after_extra_time_follows_the_settlement_policy() {
+        let game = build_game("PEN", Some((1, 1)), Some(false));
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::RegularTime),
+            Some(Settlement::Draw)
+        );
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::Advancing),
+            Some(Settlement::Loss)
+        );
+        let game = build_game("AET", Some((1, 1)), None);
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::Advancing),
+            Some(Settlement::Draw)
+        );
+    }
+
+    #[test]
+    fn game_without_result_is_voided() {
+        let game = build_game("PST", None, None);
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::RegularTime),
+            Some(Settlement::Void)
+        );
+        let game = build_game("AWD", None, None);
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::RegularTime),
+            Some(Settlement::Void)
+        );
+        let game = build_game("AWD", Some((3, 0)), Some(true));
+        assert_eq!(
+            game.get_settlement(&SettlementPolicy::RegularTime),
+            Some(Settlement::Win)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn only_the_games_played_have_kicked_off() {
+        assert!(build_game("1H", Some((0, 0)), None).has_kicked_off());
+        assert!(build_game("FT", Some((1, 0)), Some(true)).has_kicked_off());
+        for status in ["NS", "PST", "CANC", "ABD", "AWD"] {
+            let game = build_game(status, None, None);
+            assert!(!game.has_kicked_off());
+        }
+        assert!(build_game("PST", None, None).is_started());
+    }
+
+    #[test]
+    fn game_being_played_isn_t_settled() {
+        let game = build_game("2H", Some((1, 0)), None);
+        assert_eq!(game.get_settlement(&SettlementPolicy::RegularTime), None);
+        let game = build_game("FT", None, None);
+        assert_eq!(game.get_settlement(&SettlementPolicy::RegularTime), None);
+    }
+}
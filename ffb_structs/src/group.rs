@@ -0,0 +1,298 @@
+//! A group is a MySQL structure gathering users that compete together on
+//! their own leaderboard.
+//!
+//! A group is created by a user who shares its invite code with the users he
+//! wants to compete with. A group can be restricted to some competitions, in
+//! which case only the bets made on the games of these competitions count on
+//! its leaderboard.
+
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::scoreboard;
+use crate::transaction_result::TransactionResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{mysql::MySqlDatabaseError, Connection, MySqlConnection};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The length of the invite codes.
+const INVITE_CODE_LENGTH: usize = 8;
+/// The number of invite codes generated for a new group before giving up,
+/// in case they are already used by other groups.
+const INVITE_CODE_ATTEMPTS: usize = 5;
+/// The MySQL error number of a duplicate entry on a unique key.
+const DUPLICATE_ENTRY_ERROR: u16 = 1062;
+
+/// A group as it is stored within the MySQL database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct Row {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    pub(crate) invite_code: String,
+    pub(crate) owner_id: u32,
+    pub(crate) members: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Model {
+    /// The MySQL ID.
+    pub id: u32,
+    /// The name of the group.
+    pub name: String,
+    /// The code to share to invite other users in the group.
+    pub invite_code: String,
+    /// The user who created the group.
+    pub owner_id: u32,
+    /// The number of members of the group.
+    pub members: i64,
+    /// The competitions the group is restricted to.
+    ///
+    /// The group isn't restricted if empty.
+    pub league_ids: Vec<u32>,
+}
+
+impl From<Row> for Model {
+    fn from(row: Row) -> Self {
+        Model {
+            id: row.id,
+            name: row.name,
+            invite_code: row.invite_code,
+            owner_id: row.owner_id,
+            members: row.members,
+            league_ids: Vec::new(),
+        }
+    }
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Create a new group, its creator being its first member.
+    ///
+    /// A new invite code is generated if the first one is already used by
+    /// another group.
+    ///
+    /// Returns the created group.
+    ///
+    /// # Arguments
+    ///
+    /// - owner_id : the id of the user who creates the group.
+    /// - name : the name of the group.
+    /// - league_ids : the competitions the group is restricted to.
+    pub async fn create(
+        owner_id: u32,
+        name: &str,
+        league_ids: Vec<u32>,
+    ) -> Result<Model, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut tx = conn.begin().await?;
+        let mut attempt: usize = 1;
+        let (group_id, invite_code): (u32, String) = loop {
+            let invite_code: String =
+                Uuid::new_v4().to_simple().to_string()[..INVITE_CODE_LENGTH].to_uppercase();
+            match sqlx::query("INSERT INTO USER_GROUP(name, invite_code, owner_id) VALUES(?,?,?)")
+                .bind(name)
+                .bind(&invite_code)
+                .bind(owner_id)
+                .execute(&mut tx)
+                .await
+            {
+                Ok(insert_result) => break (insert_result.last_insert_id() as u32, invite_code),
+                // The invite code is the only unique key besides the id.
+                Err(sqlx::Error::Database(err))
+                    if attempt < INVITE_CODE_ATTEMPTS
+                        && err
+                            .try_downcast_ref::<MySqlDatabaseError>()
+                            .map(MySqlDatabaseError::number)
+                            == Some(DUPLICATE_ENTRY_ERROR) =>
+                {
+                    warn!(
+                        "The invite code {} is already used, generating another one",
+                        invite_code
+                    );
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+        sqlx::query("INSERT INTO USER_GROUP_MEMBER(group_id, user_id) VALUES(?,?)")
+            .bind(group_id)
+            .bind(owner_id)
+            .execute(&mut tx)
+            .await?;
+        for league_id in &league_ids {
+            sqlx::query("INSERT IGNORE INTO USER_GROUP_LEAGUE(group_id, league_id) VALUES(?,?)")
+                .bind(group_id)
+                .bind(league_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        debug!("The user {} has created the group {}", owner_id, group_id);
+        Ok(Model {
+            id: group_id,
+            name: name.into(),
+            invite_code,
+            owner_id,
+            members: 1,
+            league_ids,
+        })
+    }
+
+    /// Add a user to the group matching the invite code.
+    ///
+    /// Returns the joined group.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user joining the group.
+    /// - invite_code : the invite code of the group.
+    pub async fn join(user_id: u32, invite_code: &str) -> Result<Model, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let group_id: Option<(u32,)> =
+            sqlx::query_as("SELECT id FROM USER_GROUP WHERE invite_code=?")
+                .bind(invite_code.trim().to_uppercase())
+                .fetch_optional(&mut conn)
+                .await?;
+        let (group_id,) = group_id.ok_or_else(|| {
+            warn!(
+                "User {} has tried to join a group with the invalid code {}",
+                user_id, invite_code
+            );
+            ApplicationError::InvalidInviteCode(invite_code.into())
+        })?;
+        sqlx::query("INSERT IGNORE INTO USER_GROUP_MEMBER(group_id, user_id) VALUES(?,?)")
+            .bind(group_id)
+            .bind(user_id)
+            .execute(&mut conn)
+            .await?;
+        debug!("The user {} has joined the group {}", user_id, group_id);
+        scoreboard::Entity::clear_cache()?;
+        Self::find_by_id_for_member(&mut conn, group_id, user_id)
+            .await?
+            .ok_or(ApplicationError::InvalidInviteCode(invite_code.into()))
+    }
+
+    /// Remove a user from a group.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user leaving the group.
+    /// - group_id : the group to leave.
+    pub async fn leave(user_id: u32, group_id: u32) -> Result<TransactionResult, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let result = sqlx::query("DELETE FROM USER_GROUP_MEMBER WHERE group_id=? AND user_id=?")
+            .bind(group_id)
+            .bind(user_id)
+            .execute(&mut conn)
+            .await?;
+        debug!("The user {} has left the group {}", user_id, group_id);
+        scoreboard::Entity::clear_cache()?;
+        Ok(TransactionResult::expect_single_result(
+            result.rows_affected(),
+        ))
+    }
+
+    /// Get the groups a user is a member of.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub async fn get_user_groups(user_id: u32) -> Result<Vec<Model>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let rows: Vec<Row> = sqlx::query_as(
+            "SELECT grp.id, grp.name, grp.invite_code, grp.owner_id, (SELECT COUNT(*) FROM USER_GROUP_MEMBER WHERE group_id = grp.id) AS `members` FROM USER_GROUP grp INNER JOIN USER_GROUP_MEMBER mbr ON mbr.group_id = grp.id WHERE mbr.user_id=? ORDER BY grp.name",
+        )
+        .bind(user_id)
+        .fetch_all(&mut conn)
+        .await?;
+        let mut groups: Vec<Model> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut group: Model = row.into();
+            group.league_ids = Self::get_league_ids(&mut conn, group.id).await?;
+            groups.push(group);
+        }
+        Ok(groups)
+    }
+
+    /// Get the users sharing at least a group with the given user.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    pub async fn get_group_mate_ids(user_id: u32) -> Result<HashSet<u32>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let user_ids: Vec<(u32,)> = sqlx::query_as(
+            "SELECT DISTINCT mate.user_id FROM USER_GROUP_MEMBER mbr INNER JOIN USER_GROUP_MEMBER mate ON mate.group_id = mbr.group_id WHERE mbr.user_id=? AND mate.user_id<>?",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&mut conn)
+        .await?;
+        Ok(user_ids.into_iter().map(|(user_id,)| user_id).collect())
+    }
+
+    /// Get a group if the given user is one of its members.
+    ///
+    /// # Arguments
+    ///
+    /// - group_id : the MySQL group id.
+    /// - user_id : the MySQL user id.
+    pub async fn find_by_id_for_user(
+        group_id: u32,
+        user_id: u32,
+    ) -> Result<Option<Model>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        Self::find_by_id_for_member(&mut conn, group_id, user_id).await
+    }
+
+    /// Get a group if the given user is one of its members.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection to use.
+    /// - group_id : the MySQL group id.
+    /// - user_id : the MySQL user id.
+    async fn find_by_id_for_member(
+        conn: &mut MySqlConnection,
+        group_id: u32,
+        user_id: u32,
+    ) -> Result<Option<Model>, ApplicationError> {
+        let row: Option<Row> = sqlx::query_as(
+            "SELECT grp.id, grp.name, grp.invite_code, grp.owner_id, (SELECT COUNT(*) FROM USER_GROUP_MEMBER WHERE group_id = grp.id) AS `members` FROM USER_GROUP grp INNER JOIN USER_GROUP_MEMBER mbr ON mbr.group_id = grp.id WHERE grp.id=? AND mbr.user_id=?",
+        )
+        .bind(group_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        match row {
+            Some(row) => {
+                let mut group: Model = row.into();
+                group.league_ids = Self::get_league_ids(conn, group.id).await?;
+                Ok(Some(group))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get the competitions a group is restricted to.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection to use.
+    /// - group_id : the MySQL group id.
+    pub(crate) async fn get_league_ids(
+        conn: &mut MySqlConnection,
+        group_id: u32,
+    ) -> Result<Vec<u32>, ApplicationError> {
+        let league_ids: Vec<(u32,)> =
+            sqlx::query_as("SELECT league_id FROM USER_GROUP_LEAGUE WHERE group_id=?")
+                .bind(group_id)
+                .fetch_all(conn)
+                .await?;
+        Ok(league_ids
+            .into_iter()
+            .map(|(league_id,)| league_id)
+            .collect())
+    }
+}
@@ -0,0 +1,127 @@
+//! A job lock is a Redis key held by the process running a job of the CLI.
+//!
+//! Several replicas of the CLI's daemon can run side by side, the lock makes
+//! sure that a job isn't run by two of them at the same time. The lock expires
+//! on its own, so that a process that died while running a job doesn't hold
+//! it forever, and is extended by the process while the job is running.
+//!
+//! The time each job has last been run is stored in Redis as well, so that the
+//! replicas take turns and a job is run once per interval whatever the number
+//! of replicas.
+
+use crate::database::Database;
+use crate::error::ApplicationError;
+use uuid::Uuid;
+
+/// The script releasing a lock only if it is still held with the given token.
+const RELEASE_SCRIPT: &str =
+    "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+/// The script extending a lock only if it is still held with the given token.
+const EXTEND_SCRIPT: &str =
+    "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('EXPIRE', KEYS[1], ARGV[2]) else return 0 end";
+/// The script claiming the turn of a job if its delay has elapsed since its
+/// last run, on the clock of the Redis server.
+const CLAIM_SCRIPT: &str =
+    "local now = tonumber(redis.call('TIME')[1]) local last = redis.call('GET', KEYS[1]) if last and now < tonumber(last) + tonumber(ARGV[1]) then return 0 end redis.call('SET', KEYS[1], now) return 1";
+
+pub struct Entity;
+
+impl Entity {
+    /// Try to acquire the lock of a job.
+    ///
+    /// Returns the token of the lock if it has been acquired, none if another
+    /// process holds it.
+    ///
+    /// # Arguments
+    ///
+    /// - job : the name of the job.
+    /// - expiration : the time in seconds after which the lock expires.
+    pub fn acquire(job: &str, expiration: u64) -> Result<Option<String>, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let token: String = Uuid::new_v4().to_string();
+        let result: Option<String> = redis::cmd("SET")
+            .arg(format!("job_lock::{}", job))
+            .arg(&token)
+            .arg("NX")
+            .arg("EX")
+            .arg(expiration)
+            .query(&mut conn)?;
+        match result {
+            Some(_) => {
+                debug!("The lock of job {} has been acquired", job);
+                Ok(Some(token))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Claim the turn of a job, if it hasn't been run by any process for the
+    /// given delay.
+    ///
+    /// Returns whether the turn has been claimed, in which case the job is
+    /// considered as run from now on.
+    ///
+    /// # Arguments
+    ///
+    /// - job : the name of the job.
+    /// - delay : the time in seconds to wait between two runs of the job.
+    pub fn claim_turn(job: &str, delay: u64) -> Result<bool, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let claimed: i32 = redis::cmd("EVAL")
+            .arg(CLAIM_SCRIPT)
+            .arg(1)
+            .arg(format!("job_last_run::{}", job))
+            .arg(delay)
+            .query(&mut conn)?;
+        Ok(claimed != 0)
+    }
+
+    /// Extend the lock of a job, so that it doesn't expire while the job is
+    /// still running.
+    ///
+    /// Returns whether the lock is still held with the given token.
+    ///
+    /// # Arguments
+    ///
+    /// - job : the name of the job.
+    /// - token : the token returned when the lock has been acquired.
+    /// - expiration : the time in seconds after which the lock expires, from
+    ///   now on.
+    pub fn extend(job: &str, token: &str, expiration: u64) -> Result<bool, ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let extended: i32 = redis::cmd("EVAL")
+            .arg(EXTEND_SCRIPT)
+            .arg(1)
+            .arg(format!("job_lock::{}", job))
+            .arg(token)
+            .arg(expiration)
+            .query(&mut conn)?;
+        Ok(extended != 0)
+    }
+
+    /// Release the lock of a job.
+    ///
+    /// The lock isn't released if it has expired and been acquired by another
+    /// process since.
+    ///
+    /// # Arguments
+    ///
+    /// - job : the name of the job.
+    /// - token : the token returned when the lock has been acquired.
+    pub fn release(job: &str, token: &str) -> Result<(), ApplicationError> {
+        let mut conn = Database::acquire_redis_connection()?;
+        let released: i32 = redis::cmd("EVAL")
+            .arg(RELEASE_SCRIPT)
+            .arg(1)
+            .arg(format!("job_lock::{}", job))
+            .arg(token)
+            .query(&mut conn)?;
+        if released == 0 {
+            warn!(
+                "The lock of job {} had expired before the job was over",
+                job
+            );
+        }
+        Ok(())
+    }
+}
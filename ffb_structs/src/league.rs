@@ -19,6 +19,8 @@ use serde_json::{json, Value};
 use std::hash::{Hash, Hasher};
 use bson::Document;
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "cli")]
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +44,11 @@ pub struct Model {
     ///
     /// ie. 16th match day, semi-final, ...
     pub round: Option<String>,
+    /// The year the current season of the league has started.
+    ///
+    /// It is required by the API provider to fetch the games of a league.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_season: Option<u32>,
 }
 
 /// The league's model logo, used to fetch the remote logos.
@@ -131,14 +138,56 @@ impl Entity {
         Ok(())
     }
 
-    /// Stores the serialized struct within the mongo database.
-    pub async fn store(value: &str) -> Result<(), ApplicationError> {
+    /// Get the ids of the leagues followed within the application.
+    ///
+    /// They are the favorite leagues of the users, and the leagues of the
+    /// games that have been open to bets.
+    pub async fn get_followed_ids() -> Result<Vec<u32>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let rows: Vec<(u32,)> = sqlx::query_as("SELECT DISTINCT league_id FROM USER_LEAGUE")
+            .fetch_all(&mut conn)
+            .await?;
+        let mut ids: BTreeSet<u32> = rows.into_iter().map(|row| row.0).collect();
+        let database = Database::acquire_mongo_connection().await?;
+        let bet_league_ids = database
+            .collection::<game::Model>("fixture")
+            .distinct("league.id", doc! {"seasonId": {"$ne": null}}, None)
+            .await?;
+        for id in bet_league_ids {
+            if let Some(id) = id.as_i64().or_else(|| id.as_i32().map(i64::from)) {
+                ids.insert(id as u32);
+            }
+        }
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Get the current season of the given leagues.
+    ///
+    /// The leagues whose current season is unknown are left out.
+    ///
+    /// # Arguments
+    ///
+    /// - ids : the ids of the leagues.
+    pub async fn get_current_seasons(ids: &[u32]) -> Result<HashMap<u32, u32>, ApplicationError> {
+        let database = Database::acquire_mongo_connection().await?;
+        let models: Vec<Model> = database
+            .collection::<Model>("league")
+            .find(doc! {"id": {"$in": ids.to_vec()}}, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(models
+            .into_iter()
+            .filter_map(|model| model.current_season.map(|season| (model.id, season)))
+            .collect())
+    }
+
+    /// Stores the leagues within the mongo database.
+    pub async fn store(models: Vec<Model>) -> Result<(), ApplicationError> {
         let database = Database::acquire_mongo_connection().await.unwrap();
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
-        let models: Vec<Model> = serde_json::from_str(value)?;
-        debug!("The leagues have successfully been deserialized");
         for model in models {
             database
                 .collection::<Model>("league")
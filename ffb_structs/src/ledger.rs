@@ -0,0 +1,256 @@
+//! The ledger is a MySQL structure that keeps track of the users' virtual
+//! bankroll for each season.
+//!
+//! Every user starts a season with the season's starting balance. Each bet,
+//! on the result, on a secondary market or accumulating several games, then
+//! takes the amount the user has chosen from the balance, and the winnings,
+//! as the amount times the odd, are credited once the game has been settled.
+//! The voided bets are refunded.
+//!
+//! The balance is the sum of the ledger's entries for the user and season.
+
+use crate::bet_slip;
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::market_bet::Market;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, QueryBuilder};
+
+/// The kind of an entry of the ledger.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum EntryKind {
+    /// The balance given to the user at the start of the season.
+    StartingBalance = 1,
+    /// The amount taken by a bet.
+    Stake = 2,
+    /// The amount won by a bet.
+    Winnings = 3,
+    /// The amount given back for a voided bet.
+    Refund = 4,
+}
+
+/// The bet an entry of the ledger stands for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum BetRef {
+    /// A bet on the result of a fixture.
+    Bet(u32),
+    /// A bet on one of the secondary markets of a fixture.
+    MarketBet(u32, Market),
+    /// An accumulator.
+    Slip(u32),
+}
+
+impl BetRef {
+    /// Get the fixture id, the market id and the slip id of the entry.
+    ///
+    /// The ids that don't apply to the bet are 0.
+    fn get_keys(&self) -> (u32, u32, u32) {
+        match self {
+            BetRef::Bet(fixture_id) => (*fixture_id, 0, 0),
+            BetRef::MarketBet(fixture_id, market) => (*fixture_id, *market as u32, 0),
+            BetRef::Slip(slip_id) => (0, 0, *slip_id),
+        }
+    }
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Get the balance of a user for the given season.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    /// - season_id : the season to get the balance of.
+    pub async fn get_balance(user_id: u32, season_id: u32) -> Result<BigDecimal, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        Self::open_account(&mut conn, user_id, season_id).await?;
+        let (balance,): (BigDecimal,) = sqlx::query_as(
+            "SELECT IFNULL(SUM(amount), 0) FROM USER_LEDGER WHERE user_id=? AND season_id=?",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .fetch_one(&mut conn)
+        .await?;
+        Ok(balance)
+    }
+
+    /// Credit the user with the season's starting balance if it hasn't been
+    /// done yet.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - user_id : the MySQL user id.
+    /// - season_id : the season the user takes part in.
+    async fn open_account(
+        conn: &mut MySqlConnection,
+        user_id: u32,
+        season_id: u32,
+    ) -> Result<(), ApplicationError> {
+        sqlx::query(
+            "INSERT IGNORE INTO USER_LEDGER(user_id, season_id, kind_id, amount) SELECT ?, id, ?, starting_balance FROM SEASON WHERE id=?",
+        )
+        .bind(user_id)
+        .bind(EntryKind::StartingBalance)
+        .bind(season_id)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Take the amount of a bet from the user's balance.
+    ///
+    /// If the user had already made the bet, the previous amount is given
+    /// back first.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - user_id : the MySQL user id.
+    /// - season_id : the season the bet is attached to.
+    /// - bet_ref : the bet the amount is taken for.
+    /// - amount : the amount chosen by the user.
+    pub(crate) async fn stake(
+        conn: &mut MySqlConnection,
+        user_id: u32,
+        season_id: u32,
+        bet_ref: BetRef,
+        amount: u32,
+    ) -> Result<(), ApplicationError> {
+        let (fixture_id, market_id, slip_id) = bet_ref.get_keys();
+        Self::open_account(&mut *conn, user_id, season_id).await?;
+        let (balance,): (BigDecimal,) = sqlx::query_as(
+            "SELECT IFNULL(SUM(amount), 0) FROM USER_LEDGER WHERE user_id=? AND season_id=? AND NOT (fixture_id=? AND market_id=? AND slip_id=? AND kind_id=?) FOR UPDATE",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .bind(fixture_id)
+        .bind(market_id)
+        .bind(slip_id)
+        .bind(EntryKind::Stake)
+        .fetch_one(&mut *conn)
+        .await?;
+        if balance < BigDecimal::from(amount) {
+            warn!(
+                "User {} has tried to bet {} with a balance of {}",
+                user_id, amount, balance
+            );
+            return Err(ApplicationError::InsufficientBalance(amount));
+        }
+        sqlx::query(
+            "INSERT INTO USER_LEDGER(user_id, season_id, fixture_id, market_id, slip_id, kind_id, amount) VALUES(?,?,?,?,?,?,?) ON DUPLICATE KEY UPDATE amount=?",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .bind(fixture_id)
+        .bind(market_id)
+        .bind(slip_id)
+        .bind(EntryKind::Stake)
+        .bind(-i64::from(amount))
+        .bind(-i64::from(amount))
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Credit the winnings and the refunds of the bets and the market bets
+    /// made on a fixture.
+    ///
+    /// The previous credits are replaced, so that a fixture can be settled
+    /// again.
+    ///
+    /// Returns the number of entries credited.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture whose bets have just been settled.
+    pub(crate) async fn settle_fixture(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+    ) -> Result<u64, ApplicationError> {
+        sqlx::query("DELETE FROM USER_LEDGER WHERE fixture_id=? AND kind_id IN (?, ?)")
+            .bind(fixture_id)
+            .bind(EntryKind::Winnings)
+            .bind(EntryKind::Refund)
+            .execute(&mut *conn)
+            .await?;
+        let insert_result = sqlx::query(
+            "INSERT INTO USER_LEDGER(user_id, season_id, fixture_id, kind_id, amount)
+            SELECT ub.user_id, ub.season_id, ub.fixture_id, IF(ub.is_void, ?, ?), IF(ub.is_void, -led.amount, ROUND(-led.amount*ub.stake, 2))
+            FROM USER_BET ub INNER JOIN USER_LEDGER led ON led.user_id = ub.user_id AND led.season_id = ub.season_id AND led.fixture_id = ub.fixture_id AND led.market_id = 0 AND led.kind_id = ?
+            WHERE ub.fixture_id=? AND (ub.is_void OR 0 < ub.outcome)",
+        )
+        .bind(EntryKind::Refund)
+        .bind(EntryKind::Winnings)
+        .bind(EntryKind::Stake)
+        .bind(fixture_id)
+        .execute(&mut *conn)
+        .await?;
+        let market_insert_result = sqlx::query(
+            "INSERT INTO USER_LEDGER(user_id, season_id, fixture_id, market_id, kind_id, amount)
+            SELECT umb.user_id, umb.season_id, umb.fixture_id, umb.market_id, IF(umb.is_void, ?, ?), IF(umb.is_void, -led.amount, ROUND(-led.amount*umb.stake, 2))
+            FROM USER_MARKET_BET umb INNER JOIN USER_LEDGER led ON led.user_id = umb.user_id AND led.season_id = umb.season_id AND led.fixture_id = umb.fixture_id AND led.market_id = umb.market_id AND led.kind_id = ?
+            WHERE umb.fixture_id=? AND (umb.is_void OR 0 < umb.outcome)",
+        )
+        .bind(EntryKind::Refund)
+        .bind(EntryKind::Winnings)
+        .bind(EntryKind::Stake)
+        .bind(fixture_id)
+        .execute(conn)
+        .await?;
+        Ok(insert_result.rows_affected() + market_insert_result.rows_affected())
+    }
+
+    /// Credit the winnings and the refunds of the accumulators having a leg
+    /// on a fixture.
+    ///
+    /// The previous credits are replaced, so that an accumulator can be
+    /// settled again. The winnings are the amount times the product of the
+    /// odds of the legs that haven't been voided.
+    ///
+    /// Returns the number of entries credited.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture whose legs have just been settled.
+    pub(crate) async fn settle_slips(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+    ) -> Result<u64, ApplicationError> {
+        sqlx::query(
+            "DELETE FROM USER_LEDGER WHERE slip_id IN (SELECT slip_id FROM USER_BET WHERE fixture_id=? AND slip_id IS NOT NULL) AND kind_id IN (?, ?)",
+        )
+        .bind(fixture_id)
+        .bind(EntryKind::Winnings)
+        .bind(EntryKind::Refund)
+        .execute(&mut *conn)
+        .await?;
+        let mut query_builder = QueryBuilder::new(
+            "INSERT INTO USER_LEDGER(user_id, season_id, slip_id, kind_id, amount)
+            SELECT bs.user_id, bs.season_id, bs.id, IF(bs.is_void, ",
+        );
+        query_builder
+            .push_bind(EntryKind::Refund)
+            .push(", ")
+            .push_bind(EntryKind::Winnings)
+            .push(
+                "), IF(bs.is_void, -led.amount, ROUND(-led.amount*legs.odds, 2))
+                FROM BET_SLIP bs INNER JOIN (",
+            );
+        bet_slip::Entity::push_legs(&mut query_builder, fixture_id);
+        query_builder
+            .push(
+                ") legs ON legs.slip_id = bs.id
+                INNER JOIN USER_LEDGER led ON led.user_id = bs.user_id AND led.season_id = bs.season_id AND led.slip_id = bs.id AND led.kind_id = ",
+            )
+            .push_bind(EntryKind::Stake)
+            .push(" WHERE bs.is_void OR 0 < bs.outcome");
+        let insert_result = query_builder.build().execute(conn).await?;
+        Ok(insert_result.rows_affected())
+    }
+}
@@ -31,7 +31,11 @@ lazy_static! {
 #[cfg(feature = "cli")]
 pub mod api_token;
 pub mod bet;
+pub mod bet_history;
+pub mod bet_history_entry;
+pub mod bet_slip;
 pub mod bookmaker;
+pub mod challenge;
 pub mod club;
 pub(crate) mod common_api_structs;
 pub(crate) mod database;
@@ -39,16 +43,25 @@ pub(crate) mod database;
 pub mod ddos;
 pub mod error;
 pub mod game;
+pub mod group;
 pub mod info;
+#[cfg(feature = "cli")]
+pub mod job_lock;
 pub mod league;
+pub mod ledger;
+pub mod market_bet;
 #[cfg(feature = "server")]
 pub mod locale;
 pub mod navaccess;
 pub mod odd;
 pub mod role;
+pub mod score_bet;
 pub mod scoreboard;
 pub mod scoreboard_entry;
+pub mod scoreboard_snapshot;
+pub mod scoring_rule;
 pub mod season;
+pub mod tie_break_policy;
 pub mod token;
 pub mod transaction_result;
 pub(crate) mod translation;
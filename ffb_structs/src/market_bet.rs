@@ -0,0 +1,355 @@
+//! A market bet is a MySQL structure that represents a user's stake on one of
+//! the secondary markets of a game.
+//!
+//! Besides the game's result, a game can be bet on the number of goals (over
+//! or under 2.5 goals), whether both teams score or not, and the double
+//! chance. A user can make one bet per market and game.
+//!
+//! As the bets on the result, it is stored in MySQL with a replication in
+//! Mongo, the amount put on it is taken from the user's balance, and it is
+//! scored with the season's scoring rule.
+
+use crate::bet;
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::game;
+use crate::game::Model as Game;
+use crate::ledger;
+use crate::ledger::BetRef;
+use crate::scoring_rule::ScoringRule;
+use crate::transaction_result::TransactionResult;
+use chrono::{DateTime, Utc};
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, MySqlConnection, QueryBuilder};
+
+/// The goal line of the over/under market.
+pub const GOAL_LINE: f32 = 2.5;
+
+/// The secondary markets a game can be bet on.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum Market {
+    /// Whether more or less than [GOAL_LINE] goals are scored.
+    OverUnder = 1,
+    /// Whether both teams score at least once.
+    BothTeamsScore = 2,
+    /// Two of the three possible results.
+    DoubleChance = 3,
+}
+
+/// A pick on one of the secondary markets.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum MarketPick {
+    /// More than [GOAL_LINE] goals are scored.
+    Over = 1,
+    /// Less than [GOAL_LINE] goals are scored.
+    Under = 2,
+    /// Both teams score.
+    BothTeamsScore = 3,
+    /// At least one team doesn't score.
+    NotBothTeamsScore = 4,
+    /// The home team wins or the game is a draw.
+    HomeOrDraw = 5,
+    /// One of the teams wins.
+    HomeOrAway = 6,
+    /// The away team wins or the game is a draw.
+    DrawOrAway = 7,
+}
+
+impl MarketPick {
+    /// All the picks, sorted by market.
+    pub const ALL: [MarketPick; 7] = [
+        MarketPick::Over,
+        MarketPick::Under,
+        MarketPick::BothTeamsScore,
+        MarketPick::NotBothTeamsScore,
+        MarketPick::HomeOrDraw,
+        MarketPick::HomeOrAway,
+        MarketPick::DrawOrAway,
+    ];
+
+    /// The market the pick belongs to.
+    pub fn market(&self) -> Market {
+        match self {
+            MarketPick::Over | MarketPick::Under => Market::OverUnder,
+            MarketPick::BothTeamsScore | MarketPick::NotBothTeamsScore => Market::BothTeamsScore,
+            MarketPick::HomeOrDraw | MarketPick::HomeOrAway | MarketPick::DrawOrAway => {
+                Market::DoubleChance
+            }
+        }
+    }
+
+    /// Whether the pick is right given the final score.
+    ///
+    /// # Arguments
+    ///
+    /// - home : the home team's goals.
+    /// - away : the away team's goals.
+    pub fn is_won(&self, home: i16, away: i16) -> bool {
+        match self {
+            MarketPick::Over => GOAL_LINE < f32::from(home + away),
+            MarketPick::Under => f32::from(home + away) < GOAL_LINE,
+            MarketPick::BothTeamsScore => 0 < home && 0 < away,
+            MarketPick::NotBothTeamsScore => home == 0 || away == 0,
+            MarketPick::HomeOrDraw => away <= home,
+            MarketPick::HomeOrAway => home != away,
+            MarketPick::DrawOrAway => home <= away,
+        }
+    }
+
+    /// The label used to display the pick.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            MarketPick::Over => "M10010_PICK_OVER",
+            MarketPick::Under => "M10010_PICK_UNDER",
+            MarketPick::BothTeamsScore => "M10010_PICK_BTTS",
+            MarketPick::NotBothTeamsScore => "M10010_PICK_NOT_BTTS",
+            MarketPick::HomeOrDraw => "M10010_PICK_HOME_OR_DRAW",
+            MarketPick::HomeOrAway => "M10010_PICK_HOME_OR_AWAY",
+            MarketPick::DrawOrAway => "M10010_PICK_DRAW_OR_AWAY",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Model {
+    /// ID of the user who made the bet.
+    pub user_id: u32,
+    /// The fixture on which the bet stands.
+    pub fixture_id: u32,
+    /// The market the bet stands on.
+    pub market_id: Market,
+    /// The user's pick.
+    pub pick_id: MarketPick,
+    /// The associated season id.
+    pub season_id: u32,
+    /// The stake.
+    pub stake: f32,
+    /// Outcome of the bet, none until the game has been processed.
+    pub outcome: Option<i32>,
+    /// Whether the bet has been voided and refunded.
+    pub is_void: bool,
+    /// The id of the odds snapshot the bet has been placed against.
+    pub odds_snapshot_id: Option<String>,
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Upsert a market bet within the database.
+    ///
+    /// The same rules as the bets on the result apply : the game has to be a
+    /// bet of the current season with odds for the given market, and the bet
+    /// can only be made before the kickoff.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user who makes the bet.
+    /// - fixture_id : the id of the fixture the user bets on.
+    /// - pick : the user's pick.
+    /// - amount : the amount of the user's balance put on the bet.
+    pub async fn upsert_market_bet(
+        user_id: u32,
+        fixture_id: u32,
+        pick: MarketPick,
+        amount: u32,
+    ) -> Result<TransactionResult, ApplicationError> {
+        let (season, game) = bet::Entity::validate_open_bet(user_id, fixture_id).await?;
+        let season_id: u32 = season.id;
+        let league_id: u32 = game.league.id;
+        let stake: f32 = game
+            .market_odds
+            .and_then(|market_odds| market_odds.get_odd_for_pick(&pick))
+            .ok_or(ApplicationError::NoOddsForGame(fixture_id))?;
+        let odds_snapshot_id: Option<String> = game
+            .odds_snapshot_id
+            .map(|snapshot_id| snapshot_id.to_hex());
+        let market: Market = pick.market();
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut tx = conn.begin().await?;
+        ledger::Entity::stake(
+            &mut tx,
+            user_id,
+            season_id,
+            BetRef::MarketBet(fixture_id, market),
+            amount,
+        )
+        .await?;
+        let now: DateTime<Utc> = Utc::now();
+        let database = Database::acquire_mongo_connection().await?;
+        // As for the bets on the result, the bet is only replicated if the
+        // game hasn't started.
+        let mongo_result = database
+            .collection::<Game>("fixture")
+            .update_one(
+                doc! {
+                    "fixture.id" : fixture_id,
+                    "marketBetters": {
+                        "$elemMatch": {
+                            "user_id": user_id,
+                            "market": bson::to_bson(&market)?
+                        }
+                    },
+                    "fixture.timestamp": {
+                        "$gte":now.timestamp()
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "marketBetters.$.pick": bson::to_bson(&pick)?
+                    }
+                },
+                None,
+            )
+            .await?;
+        if mongo_result.matched_count == 0 {
+            let mongo_result = database
+                .collection::<Game>("fixture")
+                .update_one(
+                    doc! {
+                        "fixture.id":fixture_id,
+                        "fixture.timestamp": {
+                            "$gte":now.timestamp()
+                        }
+                    },
+                    doc! {
+                        "$addToSet": {
+                            "marketBetters" :{
+                                "user_id": user_id,
+                                "market": bson::to_bson(&market)?,
+                                "pick": bson::to_bson(&pick)?
+                            }
+                        }
+                    },
+                    None,
+                )
+                .await?;
+            if mongo_result.matched_count == 0 {
+                warn!(
+                    "User {} has tried to update a market bet after the kickoff",
+                    user_id
+                );
+                return Err(ApplicationError::FormOutdated);
+            }
+        }
+        let result = sqlx::query(
+            "INSERT INTO USER_MARKET_BET(user_id, fixture_id, league_id, market_id, pick_id, season_id, stake, odds_snapshot_id) VALUES(?,?,?,?,?,?,?,?) ON DUPLICATE KEY UPDATE pick_id=?, stake=?, odds_snapshot_id=?",
+        )
+        .bind(user_id)
+        .bind(fixture_id)
+        .bind(league_id)
+        .bind(market)
+        .bind(pick)
+        .bind(season_id)
+        .bind(stake)
+        .bind(&odds_snapshot_id)
+        .bind(pick)
+        .bind(stake)
+        .bind(&odds_snapshot_id)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        debug!(
+            "The user {} has bet on game {} with the pick {}",
+            user_id, fixture_id, pick
+        );
+        game::Entity::clear_cache()?;
+        Ok(TransactionResult::expect_single_result(
+            result.rows_affected(),
+        ))
+    }
+
+    /// Settle the market bets made on a fixture.
+    ///
+    /// Returns the number of bets updated.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture whose bets are settled.
+    /// - score : the final score, none if the fixture has been voided.
+    /// - scoring_rule : the rule used to score the bets.
+    pub(crate) async fn settle_market_bets(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+        score: Option<(i16, i16)>,
+        scoring_rule: &ScoringRule,
+    ) -> Result<u64, ApplicationError> {
+        let update_result = match score {
+            Some((home, away)) => {
+                let won_picks: Vec<MarketPick> = MarketPick::ALL
+                    .into_iter()
+                    .filter(|pick| pick.is_won(home, away))
+                    .collect();
+                let mut query_builder =
+                    QueryBuilder::new("UPDATE USER_MARKET_BET SET outcome=IF(pick_id IN (");
+                let mut separated = query_builder.separated(", ");
+                for pick in won_picks {
+                    separated.push_bind(pick);
+                }
+                separated.push_unseparated(")");
+                scoring_rule.push_points(&mut query_builder, "stake");
+                query_builder
+                    .push(", is_void=0 WHERE fixture_id=")
+                    .push_bind(fixture_id);
+                query_builder.build().execute(conn).await?
+            }
+            None => {
+                sqlx::query("UPDATE USER_MARKET_BET SET outcome=0, is_void=1 WHERE fixture_id=?")
+                    .bind(fixture_id)
+                    .execute(conn)
+                    .await?
+            }
+        };
+        Ok(update_result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn over_under_is_settled_on_the_goal_line() {
+        assert!(MarketPick::Over.is_won(2, 1));
+        assert!(!MarketPick::Over.is_won(1, 1));
+        assert!(MarketPick::Under.is_won(1, 1));
+        assert!(!MarketPick::Under.is_won(0, 3));
+    }
+
+    #[test]
+    fn both_teams_score_needs_a_goal_from_each_team() {
+        assert!(MarketPick::BothTeamsScore.is_won(1, 1));
+        assert!(!MarketPick::BothTeamsScore.is_won(3, 0));
+        assert!(MarketPick::NotBothTeamsScore.is_won(0, 0));
+        assert!(!MarketPick::NotBothTeamsScore.is_won(2, 1));
+    }
+
+    #[test]
+    fn double_chance_covers_two_results() {
+        assert!(MarketPick::HomeOrDraw.is_won(1, 1));
+        assert!(MarketPick::HomeOrDraw.is_won(2, 0));
+        assert!(!MarketPick::HomeOrDraw.is_won(0, 1));
+        assert!(MarketPick::HomeOrAway.is_won(0, 1));
+        assert!(!MarketPick::HomeOrAway.is_won(2, 2));
+        assert!(MarketPick::DrawOrAway.is_won(0, 0));
+        assert!(!MarketPick::DrawOrAway.is_won(1, 0));
+    }
+
+    #[test]
+    fn every_pick_belongs_to_one_market() {
+        for market in [
+            Market::OverUnder,
+            Market::BothTeamsScore,
+            Market::DoubleChance,
+        ] {
+            let picks: usize = MarketPick::ALL
+                .iter()
+                .filter(|pick| pick.market() == market)
+                .count();
+            assert!(2 <= picks);
+        }
+    }
+}
@@ -6,22 +6,86 @@
 //!
 //! They should be fetched once per day and refreshed for the day to come if
 //! appliable.
+//!
+//! Each time the odds are fetched, a timestamped snapshot of the normalised
+//! odds is added to the history, so that the odds movement can be followed
+//! and the bets can refer to the odds they have been placed against. Indexing
+//! the odds links the games to their latest snapshot.
 
-use crate::bookmaker::Model as Bookmaker;
+use crate::common_api_structs::{Bet, MarketOdds, Odds, Value};
 use crate::database::Database;
 use crate::error::ApplicationError;
+use crate::{bookmaker, bookmaker::Model as Bookmaker};
 use crate::{game, game::Model as Game};
+use bson::oid::ObjectId;
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 
+/// The remote API's id of the match winner bet.
+const MATCH_WINNER_BET_ID: u32 = 1;
+/// The remote API's id of the goals over/under bet.
+const GOALS_OVER_UNDER_BET_ID: u32 = 5;
+/// The remote API's id of the both teams score bet.
+const BOTH_TEAMS_SCORE_BET_ID: u32 = 8;
+/// The remote API's id of the double chance bet.
+const DOUBLE_CHANCE_BET_ID: u32 = 12;
+
+/// Why a fixture has been skipped while indexing the odds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Display)]
+pub enum SkipReason {
+    /// No bookmaker provides odds for the fixture.
+    #[display(fmt = "no bookmaker")]
+    NoBookmaker,
+    /// None of the bookmakers' odds could be read.
+    #[display(fmt = "no usable odds")]
+    NoUsableOdds,
+}
+
+/// A fixture whose odds haven't been indexed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFixture {
+    /// The remote API's fixture id.
+    pub fixture_id: u32,
+    /// Why the fixture has been skipped.
+    pub reason: SkipReason,
+}
+
+/// The outcome of an indexing run.
+#[derive(Clone, Debug, Default)]
+pub struct IndexReport {
+    /// The fixtures whose odds have been indexed.
+    pub indexed: Vec<u32>,
+    /// The fixtures that have been skipped.
+    pub skipped: Vec<SkippedFixture>,
+}
+
+/// A snapshot of the normalised odds of a fixture at a given time.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// The Mongo ID of the snapshot.
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    /// The remote API's fixture id.
+    pub fixture_id: u32,
+    /// When the snapshot has been taken, as a unix timestamp.
+    pub fetched_at: i64,
+    /// The odds on the game's result.
+    pub odds: Option<Odds>,
+    /// The odds of the secondary markets.
+    pub market_odds: Option<MarketOdds>,
+}
+
 /// Simplified fixture structure so that we don't fetch all the fields.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 struct SimplifiedFixture {
     id: u32,
 }
 
+/// The odds of a fixture, as they are fetched.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-struct Model {
+pub struct Model {
     /// The fixture to add an odd on.
     fixture: SimplifiedFixture,
     /// The list of bookmakers that have a bet available for the game.
@@ -30,23 +94,47 @@ struct Model {
     processed: Option<bool>,
 }
 
+impl Model {
+    /// The remote API's id of the fixture the odds are on.
+    pub fn get_fixture_id(&self) -> u32 {
+        self.fixture.id
+    }
+}
+
 pub struct Entity;
 
 impl Entity {
 
-    /// Stores the serialized structure within the database.
-    pub async fn store(value: &str) -> Result<(), ApplicationError> {
+    /// Stores the odds within the database.
+    ///
+    /// A snapshot of the normalised odds of each fixture is added to the
+    /// history, stamped with the time the odds have been fetched at.
+    pub async fn store(models: Vec<Model>) -> Result<(), ApplicationError> {
         let database = Database::acquire_mongo_connection().await?;
         debug!("Starting to store the odds within the database");
+        let fetched_at: i64 = Utc::now().timestamp();
+        let main_bookmaker_id: Option<u32> = bookmaker::Entity::get_main_bookmaker_id().await?;
         let update_options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
-        let models: Vec<Model> = serde_json::from_str(value)?;
         for model in models {
+            if let Some((odds, market_odds)) = Self::read_odds(&model, main_bookmaker_id) {
+                let snapshot: Snapshot = Snapshot {
+                    id: None,
+                    fixture_id: model.fixture.id,
+                    fetched_at,
+                    odds,
+                    market_odds,
+                };
+                database
+                    .collection::<Snapshot>("odd_history")
+                    .insert_one(&snapshot, None)
+                    .await?;
+            }
             database
                 .collection::<Model>("odd")
                 .update_one(
-                    doc! {"fixture_id": model.fixture.id},
+                    doc! {"fixture.id": model.fixture.id},
                     doc! {"$set": bson::to_bson(&model)?},
                     update_options.clone(),
                 )
@@ -56,8 +144,70 @@ impl Entity {
         Ok(())
     }
 
+    /// Read the normalised odds of a fixture, as they are fetched.
+    ///
+    /// The odds are read from the main bookmaker if it provides them, from
+    /// the other bookmakers otherwise. The values are matched on their labels
+    /// rather than on their position.
+    ///
+    /// Returns none if no odds could be read.
+    ///
+    /// # Arguments
+    ///
+    /// - model : the odds of the fixture, as they are fetched.
+    /// - main_bookmaker_id : the id of the main bookmaker, if any.
+    fn read_odds(
+        model: &Model,
+        main_bookmaker_id: Option<u32>,
+    ) -> Option<(Option<Odds>, Option<MarketOdds>)> {
+        // The main bookmaker is looked up first, the others keep the order
+        // given by the remote API.
+        let mut bookmakers: Vec<&Bookmaker> = model.bookmakers.iter().collect();
+        bookmakers.sort_by_key(|bookmaker| Some(bookmaker.id) != main_bookmaker_id);
+        let odds: Option<Odds> =
+            Self::find_odds(&bookmakers, MATCH_WINNER_BET_ID, ["Home", "Draw", "Away"])
+                .map(|[home, draw, away]| Odds { home, draw, away });
+        let mut market_odds: MarketOdds = MarketOdds::default();
+        if let Some([over, under]) = Self::find_odds(
+            &bookmakers,
+            GOALS_OVER_UNDER_BET_ID,
+            ["Over 2.5", "Under 2.5"],
+        ) {
+            market_odds.over = Some(over);
+            market_odds.under = Some(under);
+        }
+        if let Some([yes, no]) =
+            Self::find_odds(&bookmakers, BOTH_TEAMS_SCORE_BET_ID, ["Yes", "No"])
+        {
+            market_odds.both_teams_score = Some(yes);
+            market_odds.not_both_teams_score = Some(no);
+        }
+        if let Some([home_or_draw, home_or_away, draw_or_away]) = Self::find_odds(
+            &bookmakers,
+            DOUBLE_CHANCE_BET_ID,
+            ["Home/Draw", "Home/Away", "Draw/Away"],
+        ) {
+            market_odds.home_or_draw = Some(home_or_draw);
+            market_odds.home_or_away = Some(home_or_away);
+            market_odds.draw_or_away = Some(draw_or_away);
+        }
+        let market_odds: Option<MarketOdds> = Some(market_odds)
+            .filter(|market_odds| !market_odds.get_available_picks().is_empty());
+        match (odds, market_odds) {
+            (None, None) => None,
+            (odds, market_odds) => Some((odds, market_odds)),
+        }
+    }
+
     /// Index the odds so that they are linked with their actual games.
-    pub async fn index() -> Result<(), ApplicationError> {
+    ///
+    /// The games are given the odds of the latest snapshot taken for them,
+    /// and their opening odds are the ones of the first snapshot.
+    ///
+    /// A fixture whose odds can't be read is skipped and reported rather than
+    /// failing the whole run. It is marked as processed as well, and is only
+    /// indexed again once its odds have been fetched again.
+    pub async fn index() -> Result<IndexReport, ApplicationError> {
         let database = Database::acquire_mongo_connection().await?;
         debug!("Starting to index the odds");
         let models: Vec<Model> = database
@@ -66,29 +216,244 @@ impl Entity {
             .await?
             .try_collect()
             .await?;
+        let latest_options = mongodb::options::FindOneOptions::builder()
+            .sort(doc! {"fetchedAt": -1, "_id": -1})
+            .build();
+        let first_options = mongodb::options::FindOneOptions::builder()
+            .sort(doc! {"fetchedAt": 1, "_id": 1})
+            .build();
+        let mut report: IndexReport = IndexReport::default();
         for model in models {
-            if let Some(bets) = model.bookmakers[0].bets.clone() {
-                if bets[0].values.len() == 3 {
-                    let home_odd: f32 = bets[0].values[0].odd.parse()?;
-                    let draw_odd: f32 = bets[0].values[1].odd.parse()?;
-                    let away_odd: f32 = bets[0].values[2].odd.parse()?;
-                    database
-                .collection::<Game>("fixture")
-                .update_one(
-                    doc! {"fixture.id": model.fixture.id},
-                    doc! {"$set": {"odds": {"home":home_odd, "draw": draw_odd, "away":away_odd}}},
-                    None,
-                )
+            let fixture_id: u32 = model.fixture.id;
+            let snapshot: Option<Snapshot> = database
+                .collection::<Snapshot>("odd_history")
+                .find_one(doc! {"fixtureId": fixture_id}, latest_options.clone())
                 .await?;
+            let (snapshot_id, odds, market_odds) = match snapshot {
+                Some(Snapshot {
+                    id: Some(snapshot_id),
+                    odds,
+                    market_odds,
+                    ..
+                }) => (snapshot_id, odds, market_odds),
+                _ if model.bookmakers.is_empty() => {
+                    warn!("No bookmaker provides odds for fixture {}", fixture_id);
+                    report.skipped.push(SkippedFixture {
+                        fixture_id,
+                        reason: SkipReason::NoBookmaker,
+                    });
+                    continue;
+                }
+                _ => {
+                    warn!("No usable odds have been found for fixture {}", fixture_id);
+                    report.skipped.push(SkippedFixture {
+                        fixture_id,
+                        reason: SkipReason::NoUsableOdds,
+                    });
+                    continue;
+                }
+            };
+            let mut update = doc! {"oddsSnapshotId": snapshot_id};
+            if let Some(odds) = odds {
+                update.insert("odds", bson::to_bson(&odds)?);
+                let first_snapshot: Option<Snapshot> = database
+                    .collection::<Snapshot>("odd_history")
+                    .find_one(
+                        doc! {"fixtureId": fixture_id, "odds": {"$ne": null}},
+                        first_options.clone(),
+                    )
+                    .await?;
+                if let Some(opening_odds) = first_snapshot.and_then(|snapshot| snapshot.odds) {
+                    // The opening odds are only set once.
+                    database
+                        .collection::<Game>("fixture")
+                        .update_one(
+                            doc! {"fixture.id": fixture_id, "openingOdds": {"$exists": false}},
+                            doc! {"$set": {"openingOdds": bson::to_bson(&opening_odds)?}},
+                            None,
+                        )
+                        .await?;
                 }
             }
+            if let Some(market_odds) = market_odds {
+                update.insert("marketOdds", bson::to_bson(&market_odds)?);
+            }
+            database
+                .collection::<Game>("fixture")
+                .update_one(doc! {"fixture.id": fixture_id}, doc! {"$set": update}, None)
+                .await?;
+            report.indexed.push(fixture_id);
         }
+        let processed_ids: Vec<u32> = report
+            .indexed
+            .iter()
+            .copied()
+            .chain(report.skipped.iter().map(|skipped| skipped.fixture_id))
+            .collect();
         database
             .collection::<Model>("odd")
-            .update_many(doc! {}, doc! {"$set": {"processed": true}}, None)
+            .update_many(
+                doc! {"fixture.id": {"$in": processed_ids}},
+                doc! {"$set": {"processed": true}},
+                None,
+            )
             .await?;
         game::Entity::clear_cache()?;
-        debug!("Odds have been processed with success");
-        Ok(())
+        debug!(
+            "Odds have been processed, {} fixtures indexed and {} skipped",
+            report.indexed.len(),
+            report.skipped.len()
+        );
+        Ok(report)
+    }
+
+    /// Find the odds of the given values in the first bookmaker that
+    /// provides all of them.
+    ///
+    /// # Arguments
+    ///
+    /// - bookmakers : the bookmakers, in the order they are looked up.
+    /// - bet_id : the remote API's bet id.
+    /// - labels : the labels of the values, as given by the remote API.
+    fn find_odds<const N: usize>(
+        bookmakers: &[&Bookmaker],
+        bet_id: u32,
+        labels: [&str; N],
+    ) -> Option<[f32; N]> {
+        bookmakers.iter().find_map(|bookmaker| {
+            let bet: &Bet = bookmaker
+                .bets
+                .as_ref()?
+                .iter()
+                .find(|bet| bet.id == bet_id)?;
+            let mut odds: [f32; N] = [0.0; N];
+            for (odd, label) in odds.iter_mut().zip(labels) {
+                *odd = Self::get_value_odd(bookmaker.id, bet, label)?;
+            }
+            Some(odds)
+        })
+    }
+
+    /// Get the odd of a bet's value, if the bet provides a valid one.
+    ///
+    /// # Arguments
+    ///
+    /// - bookmaker_id : the bookmaker providing the bet.
+    /// - bet : the bookmaker's bet.
+    /// - label : the label of the value, as given by the remote API.
+    fn get_value_odd(bookmaker_id: u32, bet: &Bet, label: &str) -> Option<f32> {
+        let value: &Value = bet
+            .values
+            .iter()
+            .find(|value| value.value.trim().eq_ignore_ascii_case(label))?;
+        match value.odd.trim().parse::<f32>() {
+            Ok(odd) if odd.is_finite() && 0.0 < odd => Some(odd),
+            _ => {
+                warn!(
+                    "The odd {} of bookmaker {} for {} can't be read",
+                    value.odd, bookmaker_id, label
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_bet(id: u32, values: &[(&str, &str)]) -> Bet {
+        Bet {
+            id,
+            name: String::new(),
+            values: values
+                .iter()
+                .map(|(value, odd)| Value {
+                    value: value.to_string(),
+                    odd: odd.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn build_bookmaker(id: u32, bets: Vec<Bet>) -> Bookmaker {
+        Bookmaker {
+            id,
+            name: String::new(),
+            is_main_bookmaker: None,
+            bets: Some(bets),
+        }
+    }
+
+    #[test]
+    fn value_odd_is_matched_on_its_label() {
+        let bet = build_bet(1, &[("Away", "3.10"), (" home ", "1.85"), ("Draw", "3.40")]);
+        assert_eq!(Entity::get_value_odd(1, &bet, "Home"), Some(1.85));
+        assert_eq!(Entity::get_value_odd(1, &bet, "Away"), Some(3.10));
+        assert_eq!(Entity::get_value_odd(1, &bet, "Over 2.5"), None);
+    }
+
+    #[test]
+    fn invalid_value_odd_is_rejected() {
+        let bet = build_bet(1, &[("Home", "N/A"), ("Draw", "0"), ("Away", "-2.5")]);
+        assert_eq!(Entity::get_value_odd(1, &bet, "Home"), None);
+        assert_eq!(Entity::get_value_odd(1, &bet, "Draw"), None);
+        assert_eq!(Entity::get_value_odd(1, &bet, "Away"), None);
+    }
+
+    #[test]
+    fn odds_are_read_from_the_first_complete_bookmaker() {
+        let incomplete = build_bookmaker(
+            1,
+            vec![build_bet(
+                MATCH_WINNER_BET_ID,
+                &[("Home", "1.50"), ("Draw", "4.00")],
+            )],
+        );
+        let invalid = build_bookmaker(
+            2,
+            vec![build_bet(
+                MATCH_WINNER_BET_ID,
+                &[("Home", "1.60"), ("Draw", ""), ("Away", "5.00")],
+            )],
+        );
+        let complete = build_bookmaker(
+            3,
+            vec![build_bet(
+                MATCH_WINNER_BET_ID,
+                &[("Home", "1.70"), ("Draw", "3.80"), ("Away", "4.50")],
+            )],
+        );
+        let bookmakers: Vec<&Bookmaker> = vec![&incomplete, &invalid, &complete];
+        assert_eq!(
+            Entity::find_odds(&bookmakers, MATCH_WINNER_BET_ID, ["Home", "Draw", "Away"]),
+            Some([1.70, 3.80, 4.50])
+        );
+        assert_eq!(
+            Entity::find_odds(&bookmakers, BOTH_TEAMS_SCORE_BET_ID, ["Yes", "No"]),
+            None
+        );
+    }
+
+    #[test]
+    fn bookmaker_without_bets_is_skipped() {
+        let empty = Bookmaker {
+            id: 1,
+            name: String::new(),
+            is_main_bookmaker: Some(true),
+            bets: None,
+        };
+        let other = build_bookmaker(
+            2,
+            vec![build_bet(
+                BOTH_TEAMS_SCORE_BET_ID,
+                &[("Yes", "1.90"), ("No", "1.90")],
+            )],
+        );
+        assert_eq!(
+            Entity::find_odds(&[&empty, &other], BOTH_TEAMS_SCORE_BET_ID, ["Yes", "No"]),
+            Some([1.90, 1.90])
+        );
     }
 }
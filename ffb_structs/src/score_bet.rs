@@ -0,0 +1,195 @@
+//! A score bet is a MySQL structure that represents a user's prediction of the
+//! exact final score of a game.
+//!
+//! Like the bets on the game's result, it is stored mainly in MySQL with a
+//! replication in Mongo. Once the game result is known, the prediction is
+//! graded : the exact score earns the most points, the right goal difference
+//! fewer, and the right result fewer again. A wrong prediction is penalized
+//! when the season's scoring rule penalizes the wrong picks.
+
+use crate::bet;
+use crate::database::Database;
+use crate::error::ApplicationError;
+use crate::game;
+use crate::game::Model as Game;
+use crate::scoring_rule::ScoringRule;
+use crate::transaction_result::TransactionResult;
+use chrono::{DateTime, Utc};
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlConnection;
+
+/// The points earned by predicting the exact score.
+pub const EXACT_SCORE_POINTS: u32 = 300;
+/// The points earned by predicting the right goal difference.
+pub const GOAL_DIFFERENCE_POINTS: u32 = 200;
+/// The points earned by predicting the right result only.
+pub const RESULT_POINTS: u32 = 100;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Model {
+    /// ID of the user who made the prediction.
+    pub user_id: u32,
+    /// The fixture on which the prediction stands.
+    pub fixture_id: u32,
+    /// The associated season id.
+    pub season_id: u32,
+    /// The predicted home team's goals.
+    pub home_goals: u8,
+    /// The predicted away team's goals.
+    pub away_goals: u8,
+    /// Outcome of the prediction, none until the game has been processed.
+    pub outcome: Option<i32>,
+    /// Whether the prediction has been voided.
+    pub is_void: bool,
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Upsert a score prediction within the database.
+    ///
+    /// The same rules as the bets on the result apply : the game has to be a
+    /// bet of the current season, and the prediction can only be made before
+    /// the kickoff.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the id of the user who makes the prediction.
+    /// - fixture_id : the id of the fixture the user predicts the score of.
+    /// - home_goals : the predicted home team's goals.
+    /// - away_goals : the predicted away team's goals.
+    pub async fn upsert_score_bet(
+        user_id: u32,
+        fixture_id: u32,
+        home_goals: u8,
+        away_goals: u8,
+    ) -> Result<TransactionResult, ApplicationError> {
+        let (season, game) = bet::Entity::validate_open_bet(user_id, fixture_id).await?;
+        let season_id: u32 = season.id;
+        let league_id: u32 = game.league.id;
+        let now: DateTime<Utc> = Utc::now();
+        let database = Database::acquire_mongo_connection().await?;
+        // As for the bets on the result, the prediction is only replicated if
+        // the game hasn't started.
+        let mongo_result = database
+            .collection::<Game>("fixture")
+            .update_one(
+                doc! {
+                    "fixture.id" : fixture_id,
+                    "scoreBetters.user_id": user_id,
+                    "fixture.timestamp": {
+                        "$gte":now.timestamp()
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "scoreBetters.$.home": i32::from(home_goals),
+                        "scoreBetters.$.away": i32::from(away_goals),
+                    }
+                },
+                None,
+            )
+            .await?;
+        if mongo_result.matched_count == 0 {
+            let mongo_result = database
+                .collection::<Game>("fixture")
+                .update_one(
+                    doc! {
+                        "fixture.id":fixture_id,
+                        "fixture.timestamp": {
+                            "$gte":now.timestamp()
+                        }
+                    },
+                    doc! {
+                        "$addToSet": {
+                            "scoreBetters" :{
+                                "user_id": user_id,
+                                "home": i32::from(home_goals),
+                                "away": i32::from(away_goals),
+                            }
+                        }
+                    },
+                    None,
+                )
+                .await?;
+            if mongo_result.matched_count == 0 {
+                warn!(
+                    "User {} has tried to update a score prediction after the kickoff",
+                    user_id
+                );
+                return Err(ApplicationError::FormOutdated);
+            }
+        }
+        let mut conn = Database::acquire_sql_connection().await?;
+        let result = sqlx::query(
+            "INSERT INTO USER_SCORE_BET(user_id, fixture_id, league_id, season_id, home_goals, away_goals) VALUES(?,?,?,?,?,?) ON DUPLICATE KEY UPDATE home_goals=?, away_goals=?",
+        )
+        .bind(user_id)
+        .bind(fixture_id)
+        .bind(league_id)
+        .bind(season_id)
+        .bind(home_goals)
+        .bind(away_goals)
+        .bind(home_goals)
+        .bind(away_goals)
+        .execute(&mut conn)
+        .await?;
+        debug!(
+            "The user {} has predicted the score {}-{} on game {}",
+            user_id, home_goals, away_goals, fixture_id
+        );
+        game::Entity::clear_cache()?;
+        Ok(TransactionResult::expect_single_result(
+            result.rows_affected(),
+        ))
+    }
+
+    /// Grade the score predictions made on a fixture.
+    ///
+    /// Returns the number of predictions updated.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection or transaction to use.
+    /// - fixture_id : the fixture whose predictions are graded.
+    /// - score : the final score, none if the fixture has been voided.
+    /// - scoring_rule : the rule giving the points of a wrong prediction.
+    pub(crate) async fn settle_score_bets(
+        conn: &mut MySqlConnection,
+        fixture_id: u32,
+        score: Option<(i16, i16)>,
+        scoring_rule: &ScoringRule,
+    ) -> Result<u64, ApplicationError> {
+        let update_result = match score {
+            Some((home, away)) => {
+                sqlx::query(
+                    "UPDATE USER_SCORE_BET SET outcome=CASE
+                        WHEN home_goals=? AND away_goals=? THEN ?
+                        WHEN CAST(home_goals AS SIGNED)-CAST(away_goals AS SIGNED)=? THEN ?
+                        WHEN SIGN(CAST(home_goals AS SIGNED)-CAST(away_goals AS SIGNED))=SIGN(?) THEN ?
+                        ELSE ?
+                    END, is_void=0 WHERE fixture_id=?",
+                )
+                .bind(home)
+                .bind(away)
+                .bind(EXACT_SCORE_POINTS)
+                .bind(home - away)
+                .bind(GOAL_DIFFERENCE_POINTS)
+                .bind(home - away)
+                .bind(RESULT_POINTS)
+                .bind(scoring_rule.get_wrong_pick_points())
+                .bind(fixture_id)
+                .execute(conn)
+                .await?
+            }
+            None => {
+                sqlx::query("UPDATE USER_SCORE_BET SET outcome=0, is_void=1 WHERE fixture_id=?")
+                    .bind(fixture_id)
+                    .execute(conn)
+                    .await?
+            }
+        };
+        Ok(update_result.rows_affected())
+    }
+}
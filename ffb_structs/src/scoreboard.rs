@@ -3,17 +3,35 @@
 //! This list is defined by the bets they have made and how good they predicted
 //! given the final results of the games.
 //!
-//! A scoreboard is associed to a season, or can be all time.
+//! A scoreboard is associed to a season, or can be all time. It can also be
+//! restricted to the members of a group, to a competition, or to the bets
+//! made within a time window such as the current week.
+//!
+//! Besides the points, each entry holds the hit rate, the average odds taken
+//! and the longest winning streak of the user, and can be broken down by pick
+//! and by competition.
+//!
+//! The users on equal terms are told apart following the season's tie-break
+//! policy, those who are still on equal terms share the same rank.
 //!
 //! Given the time complexity to display a scoreboard, it is important to cache
 //! it.
 
+use crate::bet::GameResult;
 use crate::database::Database;
 use crate::error::ApplicationError;
+use crate::group;
+use crate::league::Model as League;
+use crate::scoreboard_entry::{Breakdown, Row as ScoreEntryRow};
+use crate::tie_break_policy::TieBreakPolicy;
 use crate::{scoreboard_entry::Model as ScoreEntry, season, season::Model as Season};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::doc;
 use serde::{Deserialize, Serialize};
-use sqlx::{mysql::MySqlRow, FromRow, QueryBuilder};
+use sqlx::{mysql::MySqlRow, FromRow, MySql, MySqlConnection, QueryBuilder, Row};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -24,6 +42,83 @@ pub struct Model {
     pub score_entries: Vec<ScoreEntry>,
 }
 
+/// The criterion the scoreboard is ranked by.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+pub enum RankBy {
+    /// The points earned by the bets.
+    #[default]
+    Points,
+    /// The number of bets made.
+    BetsMade,
+    /// The points earned per bet.
+    PointsPerBet,
+    /// The virtual balance of the users.
+    Balance,
+    /// The share of the settled bets that have been won.
+    HitRate,
+    /// The average odds of the bets made.
+    AverageOdds,
+    /// The longest run of settled bets won in a row.
+    LongestStreak,
+}
+
+/// The time window the bets of a scoreboard have been made within.
+///
+/// The windows start at midnight UTC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+pub enum TimeWindow {
+    /// Since the monday of the current week.
+    ThisWeek,
+    /// The previous week, from monday to sunday.
+    LastWeek,
+    /// Since the first day of the current month.
+    ThisMonth,
+    /// The current day and the six previous ones.
+    LastSevenDays,
+    /// The current day and the twenty-nine previous ones.
+    LastThirtyDays,
+}
+
+impl TimeWindow {
+    /// All the time windows.
+    pub const ALL: [TimeWindow; 5] = [
+        TimeWindow::ThisWeek,
+        TimeWindow::LastWeek,
+        TimeWindow::ThisMonth,
+        TimeWindow::LastSevenDays,
+        TimeWindow::LastThirtyDays,
+    ];
+
+    /// The label used to display the time window.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            TimeWindow::ThisWeek => "M40001_THIS_WEEK",
+            TimeWindow::LastWeek => "M40001_LAST_WEEK",
+            TimeWindow::ThisMonth => "M40001_THIS_MONTH",
+            TimeWindow::LastSevenDays => "M40001_LAST_SEVEN_DAYS",
+            TimeWindow::LastThirtyDays => "M40001_LAST_THIRTY_DAYS",
+        }
+    }
+
+    /// Get the start of the window and its end if it is over.
+    ///
+    /// # Arguments
+    ///
+    /// - now : the current time.
+    pub fn get_bounds(&self, now: DateTime<Utc>) -> (DateTime<Utc>, Option<DateTime<Utc>>) {
+        let today: DateTime<Utc> = now.date().and_hms(0, 0, 0);
+        let monday: DateTime<Utc> =
+            today - Duration::days(today.weekday().num_days_from_monday().into());
+        match self {
+            TimeWindow::ThisWeek => (monday, None),
+            TimeWindow::LastWeek => (monday - Duration::weeks(1), Some(monday)),
+            TimeWindow::ThisMonth => (today - Duration::days((today.day() - 1).into()), None),
+            TimeWindow::LastSevenDays => (today - Duration::days(6), None),
+            TimeWindow::LastThirtyDays => (today - Duration::days(29), None),
+        }
+    }
+}
+
 pub(crate) struct Entity;
 
 impl Entity {
@@ -46,6 +141,11 @@ pub struct EntityBuilder {
     season_id: Option<u32>,
     all_time: bool,
     limit: Option<u32>,
+    rank_by: RankBy,
+    group_id: Option<u32>,
+    league_id: Option<u32>,
+    time_window: Option<TimeWindow>,
+    breakdowns: bool,
 }
 
 impl EntityBuilder {
@@ -75,10 +175,206 @@ impl EntityBuilder {
         self
     }
 
+    /// The group to restrict the scoreboard to.
+    ///
+    /// If the group is restricted to some competitions, only the bets made
+    /// on these competitions are counted. The accumulators aren't counted
+    /// then.
+    pub fn group_id(&mut self, group_id: Option<u32>) -> &mut Self {
+        self.group_id = group_id;
+        self
+    }
+
+    /// The competition to restrict the scoreboard to.
+    ///
+    /// The accumulators aren't counted then.
+    pub fn league_id(&mut self, league_id: Option<u32>) -> &mut Self {
+        self.league_id = league_id;
+        self
+    }
+
+    /// The time window to restrict the bets to, given when they have been
+    /// made.
+    pub fn time_window(&mut self, time_window: Option<TimeWindow>) -> &mut Self {
+        self.time_window = time_window;
+        self
+    }
+
+    /// The criterion to rank the users by.
+    pub fn rank_by(&mut self, rank_by: RankBy) -> &mut Self {
+        self.rank_by = rank_by;
+        self
+    }
+
+    /// Whether to break the entries down by pick and by competition.
+    pub fn breakdowns(&mut self, breakdowns: bool) -> &mut Self {
+        self.breakdowns = breakdowns;
+        self
+    }
+
+    /// Push the common table expression `ub` gathering the bets the
+    /// scoreboard is made of.
+    ///
+    /// The score predictions, the market bets and the accumulators count as
+    /// bets as well, the legs of the accumulators don't.
+    ///
+    /// # Arguments
+    ///
+    /// - query_builder : the query to push the expression to.
+    /// - season_id : the season to restrict the bets to, if any.
+    /// - league_ids : the competitions to restrict the bets to, if any.
+    /// - bounds : the time window to restrict the bets to, if any.
+    fn push_bets(
+        &self,
+        query_builder: &mut QueryBuilder<MySql>,
+        season_id: Option<u32>,
+        league_ids: &Option<Vec<u32>>,
+        bounds: Option<(DateTime<Utc>, Option<DateTime<Utc>>)>,
+    ) {
+        query_builder.push("WITH ub AS (SELECT * FROM (SELECT user_id, season_id, fixture_id, league_id, result_id, stake, outcome, is_void, datetime FROM `USER_BET` WHERE slip_id IS NULL UNION ALL SELECT user_id, season_id, fixture_id, league_id, NULL, NULL, outcome, is_void, datetime FROM `USER_SCORE_BET` UNION ALL SELECT user_id, season_id, fixture_id, league_id, NULL, stake, outcome, is_void, datetime FROM `USER_MARKET_BET` UNION ALL SELECT user_id, season_id, NULL, NULL, NULL, stake, outcome, is_void, datetime FROM `BET_SLIP`) bets");
+        query_builder.push("\nWHERE TRUE");
+        if let Some(season_id) = season_id {
+            query_builder
+                .push("\n\tAND bets.season_id=")
+                .push_bind(season_id);
+        }
+        if let Some(group_id) = self.group_id {
+            query_builder
+                .push("\n\tAND bets.user_id IN (SELECT user_id FROM USER_GROUP_MEMBER WHERE group_id=")
+                .push_bind(group_id)
+                .push(")");
+        }
+        if let Some(league_ids) = league_ids {
+            if league_ids.is_empty() {
+                query_builder.push("\n\tAND FALSE");
+            } else {
+                query_builder.push("\n\tAND bets.league_id IN (");
+                let mut separated = query_builder.separated(", ");
+                for league_id in league_ids {
+                    separated.push_bind(*league_id);
+                }
+                separated.push_unseparated(")");
+            }
+        }
+        if let Some((start, end)) = bounds {
+            query_builder
+                .push("\n\tAND bets.datetime >= ")
+                .push_bind(start.format("%Y-%m-%d %H:%M:%S").to_string());
+            if let Some(end) = end {
+                query_builder
+                    .push("\n\tAND bets.datetime < ")
+                    .push_bind(end.format("%Y-%m-%d %H:%M:%S").to_string());
+            }
+        }
+        query_builder.push(")");
+    }
+
+    /// Break the entries down by pick and by competition.
+    ///
+    /// Only the settled bets made on a fixture are taken into account.
+    ///
+    /// # Arguments
+    ///
+    /// - conn : the SQL connection to use.
+    /// - score_entries : the entries to break down.
+    /// - season_id : the season to restrict the bets to, if any.
+    /// - league_ids : the competitions to restrict the bets to, if any.
+    /// - bounds : the time window to restrict the bets to, if any.
+    async fn push_breakdowns(
+        &self,
+        conn: &mut MySqlConnection,
+        score_entries: &mut [ScoreEntry],
+        season_id: Option<u32>,
+        league_ids: &Option<Vec<u32>>,
+        bounds: Option<(DateTime<Utc>, Option<DateTime<Utc>>)>,
+    ) -> Result<(), ApplicationError> {
+        let mut query_builder = QueryBuilder::new("");
+        self.push_bets(&mut query_builder, season_id, league_ids, bounds);
+        query_builder.push("\nSELECT user_id, league_id, result_id, CAST(outcome AS SIGNED) AS `outcome` FROM ub WHERE fixture_id IS NOT NULL AND outcome IS NOT NULL AND NOT is_void");
+        let rows: Vec<MySqlRow> = query_builder.build().fetch_all(&mut *conn).await?;
+        let mut bets: Vec<(u32, u32, Option<GameResult>, i64)> = Vec::with_capacity(rows.len());
+        for row in rows {
+            bets.push((
+                row.try_get("user_id")?,
+                row.try_get("league_id")?,
+                row.try_get("result_id")?,
+                row.try_get("outcome")?,
+            ));
+        }
+        // The names of the competitions are only known by the Mongo leagues.
+        let bet_league_ids: BTreeSet<u32> =
+            bets.iter().map(|(_, league_id, _, _)| *league_id).collect();
+        let database = Database::acquire_mongo_connection().await?;
+        let league_names: HashMap<u32, String> = database
+            .collection::<League>("league")
+            .find(
+                doc! {"id": {"$in": bet_league_ids.into_iter().collect::<Vec<u32>>()}},
+                None,
+            )
+            .await?
+            .try_collect::<Vec<League>>()
+            .await?
+            .into_iter()
+            .map(|league| (league.id, league.name))
+            .collect();
+        let mut by_pick: HashMap<u32, HashMap<GameResult, Breakdown>> = HashMap::new();
+        let mut by_league: HashMap<u32, BTreeMap<String, Breakdown>> = HashMap::new();
+        for (user_id, league_id, result_id, outcome) in bets {
+            let mut breakdowns: Vec<&mut Breakdown> = Vec::with_capacity(2);
+            if let Some(result_id) = result_id {
+                breakdowns.push(
+                    by_pick
+                        .entry(user_id)
+                        .or_default()
+                        .entry(result_id)
+                        .or_default(),
+                );
+            }
+            if let Some(league_name) = league_names.get(&league_id) {
+                breakdowns.push(
+                    by_league
+                        .entry(user_id)
+                        .or_default()
+                        .entry(league_name.clone())
+                        .or_default(),
+                );
+            }
+            for breakdown in breakdowns {
+                breakdown.bets_made += 1;
+                breakdown.points += outcome;
+                if 0 < outcome {
+                    breakdown.won += 1;
+                }
+            }
+        }
+        for score_entry in score_entries {
+            if let Some(mut user_by_pick) = by_pick.remove(&score_entry.user_id) {
+                score_entry.by_pick = [GameResult::Win, GameResult::Draw, GameResult::Loss]
+                    .into_iter()
+                    .map(|game_result| {
+                        (
+                            game_result,
+                            user_by_pick.remove(&game_result).unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+            }
+            if let Some(user_by_league) = by_league.remove(&score_entry.user_id) {
+                score_entry.by_league = user_by_league.into_iter().collect();
+            }
+        }
+        Ok(())
+    }
+
     pub async fn finish(&self) -> Result<Model, ApplicationError> {
         let mut redis_conn = Database::acquire_redis_connection()?;
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);
+        // The calendar windows move, so their bounds are part of the key.
+        let bounds: Option<(DateTime<Utc>, Option<DateTime<Utc>>)> = self
+            .time_window
+            .map(|time_window| time_window.get_bounds(Utc::now()));
+        bounds.hash(&mut hasher);
         let redis_key: String = format!("scoreboard::{:x}", hasher.finish());
         let cache_result: Option<String> = redis::cmd("GETEX")
             .arg(&redis_key)
@@ -96,27 +392,86 @@ impl EntityBuilder {
                 (None, true) => None,
                 _ => Some(season::Entity::get_current_season_id().await?),
             };
-            let mut query_builder = QueryBuilder::new("SELECT ub.user_id, usr.name as `user_name`, IF(SUM(outcome) IS NULL, 0, SUM(outcome)) AS `points`, COUNT(*) AS `bets_made`, TRUNCATE(IF(SUM(OUTCOME) IS NULL, 0, SUM(OUTCOME))/COUNT(*),2) as `ppb`");
-            query_builder.push("\nFROM `USER_BET`ub INNER JOIN USER usr ON ub.user_id = usr.id");
+            // The bets keep the competition of their fixture, the bets made
+            // on the group's competitions and on the requested competition
+            // are filtered on it.
+            let mut league_ids: Option<Vec<u32>> = match self.group_id {
+                Some(group_id) => Some(group::Entity::get_league_ids(&mut conn, group_id).await?)
+                    .filter(|league_ids| !league_ids.is_empty()),
+                None => None,
+            };
+            if let Some(league_id) = self.league_id {
+                league_ids = match league_ids {
+                    Some(league_ids) if !league_ids.contains(&league_id) => Some(Vec::new()),
+                    _ => Some(vec![league_id]),
+                };
+            }
+            let season = match season_id {
+                Some(v) => season::Entity::find_by_id(v).await?,
+                None => None,
+            };
+            let tie_break_policy: TieBreakPolicy = season
+                .as_ref()
+                .map(|season| season.tie_break_policy)
+                .unwrap_or_default();
+            let mut query_builder = QueryBuilder::new("");
+            self.push_bets(&mut query_builder, season_id, &league_ids, bounds);
+            query_builder.push("\nSELECT scores.*, RANK() OVER (ORDER BY ");
+            match self.rank_by {
+                RankBy::Points => query_builder.push("points DESC"),
+                RankBy::BetsMade => query_builder.push("bets_made DESC, points DESC"),
+                RankBy::PointsPerBet => query_builder.push("ppb DESC, points DESC"),
+                RankBy::Balance => query_builder.push("balance DESC, points DESC"),
+                RankBy::HitRate => query_builder.push("hit_rate DESC, points DESC"),
+                RankBy::AverageOdds => query_builder.push("average_odds DESC, points DESC"),
+                RankBy::LongestStreak => query_builder.push("longest_streak DESC, points DESC"),
+            };
+            tie_break_policy.push_order_by(&mut query_builder);
+            query_builder.push(") AS `ranking`");
+            query_builder.push("\nFROM (SELECT ub.user_id, usr.name as `user_name`, IF(SUM(ub.outcome) IS NULL, 0, SUM(ub.outcome)) AS `points`, CAST(SUM(NOT ub.is_void) AS SIGNED) AS `bets_made`, TRUNCATE(IF(SUM(NOT ub.is_void) = 0 OR SUM(ub.outcome) IS NULL, 0, SUM(ub.outcome)/SUM(NOT ub.is_void)),2) as `ppb`");
+            // The users who haven't bet any amount yet still have their
+            // starting balance.
+            match season_id {
+                Some(season_id) => query_builder
+                    .push(", CAST(IFNULL(MAX(led.balance), (SELECT starting_balance FROM SEASON WHERE id=")
+                    .push_bind(season_id)
+                    .push(")) AS DECIMAL(14,2)) AS `balance`"),
+                None => query_builder.push(", CAST(IFNULL(MAX(led.balance), 0) AS DECIMAL(14,2)) AS `balance`"),
+            };
+            query_builder.push(", CAST(IFNULL(100*SUM(0 < ub.outcome)/SUM(ub.outcome IS NOT NULL AND NOT ub.is_void), 0) AS DECIMAL(5,2)) AS `hit_rate`, CAST(IFNULL(AVG(IF(ub.is_void, NULL, ub.stake)), 0) AS DECIMAL(8,2)) AS `average_odds`, CAST(IFNULL(MAX(streak.longest_streak), 0) AS SIGNED) AS `longest_streak`, MIN(ub.datetime) AS `first_bet`");
+            query_builder.push("\nFROM ub INNER JOIN USER usr ON ub.user_id = usr.id");
+            query_builder
+                .push("\nLEFT JOIN (SELECT user_id, SUM(amount) AS `balance` FROM `USER_LEDGER`");
             if let Some(season_id) = season_id {
-                query_builder
-                    .push("\nWHERE season_id=")
-                    .push_bind(season_id);
+                query_builder.push(" WHERE season_id=").push_bind(season_id);
             }
-            query_builder.push("\nGROUP BY user_id");
-            query_builder.push("\nORDER BY points DESC");
+            query_builder.push(" GROUP BY user_id) led ON led.user_id = ub.user_id");
+            // The settled bets are numbered in the order they have been made,
+            // a run of bets won in a row sharing the same difference between
+            // their overall number and their number among the bets won.
+            query_builder.push("\nLEFT JOIN (SELECT user_id, MAX(streak) AS `longest_streak` FROM (SELECT user_id, COUNT(*) AS `streak` FROM (SELECT user_id, 0 < outcome AS `won`, ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY datetime, fixture_id) - ROW_NUMBER() OVER (PARTITION BY user_id, 0 < outcome ORDER BY datetime, fixture_id) AS `run` FROM ub WHERE outcome IS NOT NULL AND NOT is_void) runs WHERE won GROUP BY user_id, run) streaks GROUP BY user_id) streak ON streak.user_id = ub.user_id");
+            query_builder.push("\nGROUP BY ub.user_id) scores");
+            // The users sharing the same rank are displayed in the
+            // alphabetical order.
+            query_builder.push("\nORDER BY ranking, user_name, user_id");
             if let Some(limit) = self.limit {
                 query_builder.push("\nLIMIT ").push_bind(limit);
             }
             let rows: Vec<MySqlRow> = query_builder.build().fetch_all(&mut conn).await?;
             let mut score_entries: Vec<ScoreEntry> = Vec::with_capacity(rows.len());
             for row in rows {
-                score_entries.push(ScoreEntry::from_row(&row)?);
+                score_entries.push(ScoreEntryRow::from_row(&row)?.into());
+            }
+            if self.breakdowns && !score_entries.is_empty() {
+                self.push_breakdowns(
+                    &mut conn,
+                    &mut score_entries,
+                    season_id,
+                    &league_ids,
+                    bounds,
+                )
+                .await?;
             }
-            let season = match season_id {
-                Some(v) => season::Entity::find_by_id(v).await?,
-                None => None,
-            };
             let model: Model = Model {
                 season,
                 score_entries,
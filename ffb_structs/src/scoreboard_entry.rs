@@ -1,10 +1,49 @@
 //! The scoreboard entry is a MySQL row containing the user, its name,
 //! and other important informations when displaying a scoreboard.
 
+use crate::bet::GameResult;
 use serde::{Deserialize, Serialize};
 
+/// The results of a subset of the bets of a user.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Breakdown {
+    /// The numbers of bets made.
+    pub bets_made: i64,
+    /// The number of bets won.
+    pub won: i64,
+    /// The points won.
+    pub points: i64,
+}
+
+impl Breakdown {
+    /// The share of the settled bets that have been won, in percent.
+    pub fn hit_rate(&self) -> i64 {
+        match self.bets_made {
+            0 => 0,
+            bets_made => self.won * 100 / bets_made,
+        }
+    }
+}
+
+/// The entry as it is computed by the MySQL database.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub(crate) struct Row {
+    pub(crate) ranking: u64,
+    pub(crate) user_id: u32,
+    pub(crate) user_name: String,
+    pub(crate) points: bigdecimal::BigDecimal,
+    pub(crate) bets_made: i64,
+    pub(crate) ppb: bigdecimal::BigDecimal,
+    pub(crate) balance: bigdecimal::BigDecimal,
+    pub(crate) hit_rate: bigdecimal::BigDecimal,
+    pub(crate) average_odds: bigdecimal::BigDecimal,
+    pub(crate) longest_streak: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Model {
+    /// The rank of the user, shared with the users on equal terms.
+    pub ranking: u64,
     /// The MySQL user's id.
     pub user_id: u32,
     /// The user name.
@@ -15,4 +54,39 @@ pub struct Model {
     pub bets_made: i64,
     /// The number of points per bets.
     pub ppb: bigdecimal::BigDecimal,
+    /// The virtual balance of the user.
+    pub balance: bigdecimal::BigDecimal,
+    /// The share of the settled bets that have been won, in percent.
+    pub hit_rate: bigdecimal::BigDecimal,
+    /// The average odds of the bets made.
+    pub average_odds: bigdecimal::BigDecimal,
+    /// The longest run of settled bets won in a row.
+    pub longest_streak: i64,
+    /// The results of the bets on a game's result, by pick.
+    ///
+    /// Only filled when requested to the scoreboard builder.
+    pub by_pick: Vec<(GameResult, Breakdown)>,
+    /// The results of the bets made on a fixture, by competition name.
+    ///
+    /// Only filled when requested to the scoreboard builder.
+    pub by_league: Vec<(String, Breakdown)>,
+}
+
+impl From<Row> for Model {
+    fn from(row: Row) -> Self {
+        Model {
+            ranking: row.ranking,
+            user_id: row.user_id,
+            user_name: row.user_name,
+            points: row.points,
+            bets_made: row.bets_made,
+            ppb: row.ppb,
+            balance: row.balance,
+            hit_rate: row.hit_rate,
+            average_odds: row.average_odds,
+            longest_streak: row.longest_streak,
+            by_pick: Vec::new(),
+            by_league: Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,131 @@
+//! A scoreboard snapshot is a MySQL structure keeping the ranking and points
+//! of the users at a given day of a season.
+//!
+//! The snapshots are taken once a day by the CLI, so that the change of
+//! ranking of the users since the previous day can be displayed, as well as
+//! the evolution of their ranking over a season.
+
+use crate::database::Database;
+use crate::error::ApplicationError;
+#[cfg(feature = "cli")]
+use crate::scoreboard::EntityBuilder as ScoreboardBuilder;
+#[cfg(feature = "cli")]
+use crate::season;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "cli")]
+use sqlx::{Connection, QueryBuilder};
+use std::collections::HashMap;
+
+/// The ranking of a user at a given day.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Model {
+    /// The day the snapshot has been taken, as `YYYY-MM-DD`.
+    pub day: String,
+    /// The ranking of the user on the season's scoreboard.
+    pub ranking: u32,
+    /// The points of the user.
+    pub points: bigdecimal::BigDecimal,
+}
+
+pub struct Entity;
+
+impl Entity {
+    /// Snapshot the scoreboard of the current season for the current day.
+    ///
+    /// Taking the snapshot several times a day replaces the previous one.
+    #[cfg(feature = "cli")]
+    pub async fn take_snapshot() -> Result<(), ApplicationError> {
+        let season_id: u32 = season::Entity::get_current_season_id().await?;
+        let scoreboard = ScoreboardBuilder::build()
+            .season_id(Some(season_id))
+            .finish()
+            .await?;
+        if scoreboard.score_entries.is_empty() {
+            info!("No bet has been made on season {} yet", season_id);
+            return Ok(());
+        }
+        let day: String = Utc::now().format("%Y-%m-%d").to_string();
+        let mut conn = Database::acquire_sql_connection().await?;
+        let mut tx = conn.begin().await?;
+        let mut query_builder = QueryBuilder::new(
+            "INSERT INTO SCOREBOARD_SNAPSHOT(season_id, user_id, day, ranking, points) ",
+        );
+        query_builder.push_values(&scoreboard.score_entries, |mut separated, score_entry| {
+            separated
+                .push_bind(season_id)
+                .push_bind(score_entry.user_id)
+                .push_bind(&day)
+                .push_bind(score_entry.ranking)
+                .push_bind(&score_entry.points);
+        });
+        // A previous snapshot of the day is replaced as a whole.
+        sqlx::query("DELETE FROM SCOREBOARD_SNAPSHOT WHERE season_id=? AND day=?")
+            .bind(season_id)
+            .bind(&day)
+            .execute(&mut tx)
+            .await?;
+        query_builder.build().execute(&mut tx).await?;
+        tx.commit().await?;
+        info!(
+            "The scoreboard of season {} has been snapshotted with {} entries",
+            season_id,
+            scoreboard.score_entries.len()
+        );
+        Ok(())
+    }
+
+    /// Get the rankings of the users at the latest snapshot of the season
+    /// taken before the current day.
+    ///
+    /// The users are ranked again among the group's members when a group is
+    /// given, the users on equal terms still sharing the same rank.
+    ///
+    /// # Arguments
+    ///
+    /// - season_id : the season of the snapshot.
+    /// - group_id : the group to rank the users within, if any.
+    pub async fn get_previous_rankings(
+        season_id: u32,
+        group_id: Option<u32>,
+    ) -> Result<HashMap<u32, u32>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let today: String = Utc::now().format("%Y-%m-%d").to_string();
+        let rows: Vec<(u32, u64)> = sqlx::query_as(
+            "SELECT user_id, RANK() OVER (ORDER BY ranking) FROM SCOREBOARD_SNAPSHOT WHERE season_id=? AND day=(SELECT MAX(day) FROM SCOREBOARD_SNAPSHOT WHERE season_id=? AND day < ?) AND (? IS NULL OR user_id IN (SELECT user_id FROM USER_GROUP_MEMBER WHERE group_id=?))",
+        )
+        .bind(season_id)
+        .bind(season_id)
+        .bind(&today)
+        .bind(group_id)
+        .bind(group_id)
+        .fetch_all(&mut conn)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, ranking)| (user_id, ranking as u32))
+            .collect())
+    }
+
+    /// Get the snapshots of a user for a season, from the oldest to the most
+    /// recent.
+    ///
+    /// # Arguments
+    ///
+    /// - user_id : the MySQL user id.
+    /// - season_id : the season of the snapshots.
+    pub async fn get_user_history(
+        user_id: u32,
+        season_id: u32,
+    ) -> Result<Vec<Model>, ApplicationError> {
+        let mut conn = Database::acquire_sql_connection().await?;
+        let models: Vec<Model> = sqlx::query_as(
+            "SELECT DATE_FORMAT(day, '%Y-%m-%d') AS `day`, ranking, points FROM SCOREBOARD_SNAPSHOT WHERE user_id=? AND season_id=? ORDER BY day",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .fetch_all(&mut conn)
+        .await?;
+        Ok(models)
+    }
+}
@@ -0,0 +1,96 @@
+//! The scoring rule defines how many points a bet earns once the game has been
+//! settled.
+//!
+//! Each season uses its own rule, so that a season can reward the risky picks
+//! while another one only counts the right picks. The rule applies to every
+//! kind of bet having odds : the bets on the result, the market bets and the
+//! accumulators, scored as a single pick on the product of their legs' odds.
+//!
+//! The score predictions have no odds, they keep their graded points whatever
+//! the rule is, and only lose [WRONG_PICK_PENALTY] points with the penalty
+//! rule when they earn nothing.
+
+use crate::bet::GameResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, QueryBuilder};
+
+/// The points earned by a right pick with the flat rule.
+pub const FLAT_POINTS: i32 = 100;
+/// The points lost by a wrong pick with the penalty rule.
+pub const WRONG_PICK_PENALTY: i32 = 50;
+/// The factor applied to the outcome of a bet the joker is attached to.
+pub const JOKER_MULTIPLIER: i32 = 2;
+
+/// The rules that can be used to score the bets.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum ScoringRule {
+    /// A right pick earns 100 times its odd.
+    OddsWeighted = 1,
+    /// A right pick earns [FLAT_POINTS], whatever its odd is.
+    FlatPoints = 2,
+    /// A right pick earns 100 times its odd, a wrong one loses
+    /// [WRONG_PICK_PENALTY] points.
+    WrongPickPenalty = 3,
+}
+
+impl ScoringRule {
+    /// All the rules.
+    pub const ALL: [ScoringRule; 3] = [
+        ScoringRule::OddsWeighted,
+        ScoringRule::FlatPoints,
+        ScoringRule::WrongPickPenalty,
+    ];
+
+    /// The label used to display the rule.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ScoringRule::OddsWeighted => "M30003_ODDS_WEIGHTED",
+            ScoringRule::FlatPoints => "M30003_FLAT_POINTS",
+            ScoringRule::WrongPickPenalty => "M30003_WRONG_PICK_PENALTY",
+        }
+    }
+
+    /// Push the SQL expression computing the outcome of a bet of the
+    /// USER_BET table given the game's result.
+    ///
+    /// # Arguments
+    ///
+    /// - query_builder : the query to push the expression to.
+    /// - result : the result the game has been settled on.
+    pub(crate) fn push_outcome<'a>(
+        &self,
+        query_builder: &mut QueryBuilder<'a, MySql>,
+        result: GameResult,
+    ) {
+        query_builder.push("IF(result_id=").push_bind(result);
+        self.push_points(query_builder, "stake");
+    }
+
+    /// Push the points of a right and of a wrong pick, closing the IF
+    /// expression whose condition, the pick being right, has been pushed.
+    ///
+    /// # Arguments
+    ///
+    /// - query_builder : the query to push the points to.
+    /// - odds : the SQL expression of the odds of the pick.
+    pub(crate) fn push_points<'a>(&self, query_builder: &mut QueryBuilder<'a, MySql>, odds: &str) {
+        match self {
+            ScoringRule::OddsWeighted | ScoringRule::WrongPickPenalty => {
+                query_builder.push(format!(", {}*100, ", odds))
+            }
+            ScoringRule::FlatPoints => query_builder.push(", ").push_bind(FLAT_POINTS).push(", "),
+        };
+        query_builder
+            .push_bind(self.get_wrong_pick_points())
+            .push(")");
+    }
+
+    /// Get the points earned by a wrong pick.
+    pub(crate) fn get_wrong_pick_points(&self) -> i32 {
+        match self {
+            ScoringRule::WrongPickPenalty => -WRONG_PICK_PENALTY,
+            ScoringRule::OddsWeighted | ScoringRule::FlatPoints => 0,
+        }
+    }
+}
@@ -3,12 +3,26 @@
 
 use crate::database::Database;
 use crate::error::ApplicationError;
+use crate::scoring_rule::ScoringRule;
+use crate::tie_break_policy::TieBreakPolicy;
 use crate::transaction_result::TransactionResult;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use sqlx::{QueryBuilder, FromRow, mysql::MySqlRow};
 
+/// The rule used to settle the bets of the games that went beyond the
+/// regular time.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display)]
+#[repr(u32)]
+pub enum SettlementPolicy {
+    /// The bets are settled on the score after 90 minutes of playtime.
+    RegularTime = 1,
+    /// The bets are settled on the team that advances, after extra time or
+    /// penalties if any.
+    Advancing = 2,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow, Eq, Hash)]
 pub struct Model {
     /// The MySQL ID.
@@ -23,6 +37,15 @@ pub struct Model {
     ///
     /// A closed season can't contains more bet than it already has.
     pub is_closed: bool,
+    /// How the bets of the season's games are settled.
+    pub settlement_policy: SettlementPolicy,
+    /// The virtual balance every user starts the season with.
+    pub starting_balance: u32,
+    /// How the bets on the games' result are scored.
+    pub scoring_rule: ScoringRule,
+    /// How the users on equal terms on the season's scoreboard are told
+    /// apart.
+    pub tie_break_policy: TieBreakPolicy,
 }
 
 pub struct Entity;
@@ -88,10 +111,18 @@ impl Entity {
     ///
     /// * name : Name of the new season, be aware that the season name has to be
     /// unique within the table.
-    pub async fn add_new(name: &str) -> Result<TransactionResult, ApplicationError> {
+    /// * settlement_policy : How the bets of the season's games are settled.
+    /// * starting_balance : The virtual balance every user starts with.
+    /// * scoring_rule : How the bets on the games' result are scored.
+    /// * tie_break_policy : How the users on equal terms are told apart.
+    pub async fn add_new(name: &str, settlement_policy: SettlementPolicy, starting_balance: u32, scoring_rule: ScoringRule, tie_break_policy: TieBreakPolicy) -> Result<TransactionResult, ApplicationError> {
         let mut conn = Database::acquire_sql_connection().await?;
-        let result = sqlx::query("INSERT INTO SEASON(name) VALUES (?)")
+        let result = sqlx::query("INSERT INTO SEASON(name, settlement_policy, starting_balance, scoring_rule, tie_break_policy) VALUES (?, ?, ?, ?, ?)")
             .bind(&name)
+            .bind(settlement_policy)
+            .bind(starting_balance)
+            .bind(scoring_rule)
+            .bind(tie_break_policy)
             .execute(&mut conn)
             .await?;
         Self::clear_cache()?;
@@ -0,0 +1,144 @@
+//! The tie-break policy defines how the users on equal terms on a scoreboard
+//! are told apart.
+//!
+//! Each season uses its own policy. The users who are still on equal terms
+//! once the policy has been applied share the same rank, and are displayed in
+//! the alphabetical order.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, QueryBuilder};
+
+/// A criterion telling apart the users on equal terms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TieBreaker {
+    /// The user who has made fewer bets goes first.
+    FewerBetsMade,
+    /// The user who has earned more points per bet goes first.
+    HigherPointsPerBet,
+    /// The user who has made his first bet earlier goes first.
+    EarliestBet,
+}
+
+impl TieBreaker {
+    /// The SQL ordering of the scoreboard's rows given the tie breaker.
+    fn get_order_by(&self) -> &'static str {
+        match self {
+            TieBreaker::FewerBetsMade => "bets_made ASC",
+            TieBreaker::HigherPointsPerBet => "ppb DESC",
+            TieBreaker::EarliestBet => "first_bet ASC",
+        }
+    }
+}
+
+/// The policies that can be used to break the ties.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize, Eq, Hash, sqlx::Type, Display,
+)]
+#[repr(u32)]
+pub enum TieBreakPolicy {
+    /// Fewer bets made, then higher points per bet, then earliest bet.
+    #[default]
+    FewerBetsFirst = 1,
+    /// Higher points per bet, then fewer bets made, then earliest bet.
+    HigherPointsPerBetFirst = 2,
+    /// Earliest bet, then fewer bets made, then higher points per bet.
+    EarliestBetFirst = 3,
+    /// The ties aren't broken.
+    NoTieBreak = 4,
+}
+
+impl TieBreakPolicy {
+    /// All the policies.
+    pub const ALL: [TieBreakPolicy; 4] = [
+        TieBreakPolicy::FewerBetsFirst,
+        TieBreakPolicy::HigherPointsPerBetFirst,
+        TieBreakPolicy::EarliestBetFirst,
+        TieBreakPolicy::NoTieBreak,
+    ];
+
+    /// The label used to display the policy.
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            TieBreakPolicy::FewerBetsFirst => "M30003_FEWER_BETS_FIRST",
+            TieBreakPolicy::HigherPointsPerBetFirst => "M30003_HIGHER_PPB_FIRST",
+            TieBreakPolicy::EarliestBetFirst => "M30003_EARLIEST_BET_FIRST",
+            TieBreakPolicy::NoTieBreak => "M30003_NO_TIE_BREAK",
+        }
+    }
+
+    /// The tie breakers of the policy, in the order they are applied.
+    pub fn get_tie_breakers(&self) -> &'static [TieBreaker] {
+        match self {
+            TieBreakPolicy::FewerBetsFirst => &[
+                TieBreaker::FewerBetsMade,
+                TieBreaker::HigherPointsPerBet,
+                TieBreaker::EarliestBet,
+            ],
+            TieBreakPolicy::HigherPointsPerBetFirst => &[
+                TieBreaker::HigherPointsPerBet,
+                TieBreaker::FewerBetsMade,
+                TieBreaker::EarliestBet,
+            ],
+            TieBreakPolicy::EarliestBetFirst => &[
+                TieBreaker::EarliestBet,
+                TieBreaker::FewerBetsMade,
+                TieBreaker::HigherPointsPerBet,
+            ],
+            TieBreakPolicy::NoTieBreak => &[],
+        }
+    }
+
+    /// Push the SQL ordering of the tie breakers, following the ordering of
+    /// the scoreboard's ranking criterion.
+    ///
+    /// # Arguments
+    ///
+    /// - query_builder : the query to push the ordering to.
+    pub(crate) fn push_order_by(&self, query_builder: &mut QueryBuilder<MySql>) {
+        for tie_breaker in self.get_tie_breakers() {
+            query_builder.push(", ").push(tie_breaker.get_order_by());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Execute;
+
+    #[test]
+    fn the_default_policy_breaks_ties_on_the_bets_made_first() {
+        assert_eq!(TieBreakPolicy::default(), TieBreakPolicy::FewerBetsFirst);
+        assert_eq!(
+            TieBreakPolicy::default().get_tie_breakers()[0],
+            TieBreaker::FewerBetsMade
+        );
+    }
+
+    #[test]
+    fn every_policy_uses_each_tie_breaker_at_most_once() {
+        for policy in TieBreakPolicy::ALL {
+            let tie_breakers: &[TieBreaker] = policy.get_tie_breakers();
+            for (i, tie_breaker) in tie_breakers.iter().enumerate() {
+                assert!(!tie_breakers[i + 1..].contains(tie_breaker));
+            }
+        }
+    }
+
+    #[test]
+    fn order_by_follows_the_tie_breakers() {
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("ORDER BY points DESC");
+        TieBreakPolicy::HigherPointsPerBetFirst.push_order_by(&mut query_builder);
+        assert_eq!(
+            query_builder.build().sql(),
+            "ORDER BY points DESC, ppb DESC, bets_made ASC, first_bet ASC"
+        );
+    }
+
+    #[test]
+    fn no_tie_break_keeps_the_ordering() {
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("ORDER BY points DESC");
+        TieBreakPolicy::NoTieBreak.push_order_by(&mut query_builder);
+        assert_eq!(query_builder.build().sql(), "ORDER BY points DESC");
+    }
+}
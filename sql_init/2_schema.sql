@@ -160,6 +160,10 @@ CREATE TABLE `SEASON` (
   `name` varchar(32) NOT NULL,
   `is_main` tinyint(1) NOT NULL DEFAULT '0',
   `is_closed` tinyint(1) NOT NULL DEFAULT '0',
+  `settlement_policy` int unsigned NOT NULL DEFAULT '1',
+  `starting_balance` int unsigned NOT NULL DEFAULT '1000',
+  `scoring_rule` int unsigned NOT NULL DEFAULT '1',
+  `tie_break_policy` int unsigned NOT NULL DEFAULT '1',
   PRIMARY KEY (`id`),
   UNIQUE KEY `name` (`name`)
 ) ENGINE=InnoDB AUTO_INCREMENT=13 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
@@ -212,6 +216,29 @@ CREATE TABLE `USER` (
 ) ENGINE=InnoDB AUTO_INCREMENT=72 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 /*!40101 SET character_set_client = @saved_cs_client */;
 
+--
+-- Table structure for table `BET_SLIP`
+--
+
+DROP TABLE IF EXISTS `BET_SLIP`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `BET_SLIP` (
+  `id` int unsigned NOT NULL AUTO_INCREMENT,
+  `user_id` int unsigned NOT NULL,
+  `season_id` int unsigned NOT NULL,
+  `stake` float unsigned NOT NULL,
+  `outcome` int DEFAULT NULL,
+  `is_void` tinyint(1) NOT NULL DEFAULT '0',
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `user_id` (`user_id`),
+  KEY `season_id` (`season_id`),
+  CONSTRAINT `BET_SLIP_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `BET_SLIP_ibfk_2` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
 --
 -- Table structure for table `USER_BET`
 --
@@ -222,18 +249,167 @@ DROP TABLE IF EXISTS `USER_BET`;
 CREATE TABLE `USER_BET` (
   `user_id` int unsigned NOT NULL,
   `fixture_id` int unsigned NOT NULL,
+  `league_id` int unsigned NOT NULL DEFAULT '0',
   `result_id` int unsigned NOT NULL,
   `season_id` int unsigned NOT NULL,
   `stake` float unsigned NOT NULL,
-  `outcome` int unsigned DEFAULT NULL,
+  `outcome` int DEFAULT NULL,
+  `is_void` tinyint(1) NOT NULL DEFAULT '0',
+  `odds_snapshot_id` char(24) DEFAULT NULL,
+  `slip_id` int unsigned DEFAULT NULL,
+  `joker_period` varchar(128) DEFAULT NULL,
   `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   PRIMARY KEY (`user_id`,`fixture_id`),
   UNIQUE KEY `user_id` (`user_id`,`fixture_id`),
+  UNIQUE KEY `joker` (`user_id`,`season_id`,`joker_period`),
   KEY `USER_BET_ibfk_2` (`result_id`),
   KEY `season_id` (`season_id`),
+  KEY `league_id` (`league_id`),
+  KEY `slip_id` (`slip_id`),
   CONSTRAINT `USER_BET_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
   CONSTRAINT `USER_BET_ibfk_2` FOREIGN KEY (`result_id`) REFERENCES `RESULT` (`id`) ON DELETE RESTRICT ON UPDATE RESTRICT,
-  CONSTRAINT `USER_BET_ibfk_3` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+  CONSTRAINT `USER_BET_ibfk_3` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `USER_BET_ibfk_4` FOREIGN KEY (`slip_id`) REFERENCES `BET_SLIP` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `USER_SCORE_BET`
+--
+
+DROP TABLE IF EXISTS `USER_SCORE_BET`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_SCORE_BET` (
+  `user_id` int unsigned NOT NULL,
+  `fixture_id` int unsigned NOT NULL,
+  `league_id` int unsigned NOT NULL DEFAULT '0',
+  `season_id` int unsigned NOT NULL,
+  `home_goals` tinyint unsigned NOT NULL,
+  `away_goals` tinyint unsigned NOT NULL,
+  `outcome` int DEFAULT NULL,
+  `is_void` tinyint(1) NOT NULL DEFAULT '0',
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`user_id`,`fixture_id`),
+  KEY `season_id` (`season_id`),
+  KEY `league_id` (`league_id`),
+  CONSTRAINT `USER_SCORE_BET_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `USER_SCORE_BET_ibfk_2` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `USER_MARKET_BET`
+--
+
+DROP TABLE IF EXISTS `USER_MARKET_BET`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_MARKET_BET` (
+  `user_id` int unsigned NOT NULL,
+  `fixture_id` int unsigned NOT NULL,
+  `league_id` int unsigned NOT NULL DEFAULT '0',
+  `market_id` int unsigned NOT NULL,
+  `pick_id` int unsigned NOT NULL,
+  `season_id` int unsigned NOT NULL,
+  `stake` float unsigned NOT NULL,
+  `outcome` int DEFAULT NULL,
+  `is_void` tinyint(1) NOT NULL DEFAULT '0',
+  `odds_snapshot_id` char(24) DEFAULT NULL,
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`user_id`,`fixture_id`,`market_id`),
+  KEY `season_id` (`season_id`),
+  KEY `league_id` (`league_id`),
+  CONSTRAINT `USER_MARKET_BET_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `USER_MARKET_BET_ibfk_2` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `USER_LEDGER`
+--
+
+DROP TABLE IF EXISTS `USER_LEDGER`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_LEDGER` (
+  `id` int unsigned NOT NULL AUTO_INCREMENT,
+  `user_id` int unsigned NOT NULL,
+  `season_id` int unsigned NOT NULL,
+  `fixture_id` int unsigned NOT NULL DEFAULT '0',
+  `market_id` int unsigned NOT NULL DEFAULT '0',
+  `slip_id` int unsigned NOT NULL DEFAULT '0',
+  `kind_id` int unsigned NOT NULL,
+  `amount` decimal(12,2) NOT NULL,
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `entry` (`user_id`,`season_id`,`fixture_id`,`market_id`,`slip_id`,`kind_id`),
+  KEY `season_id` (`season_id`),
+  KEY `fixture_id` (`fixture_id`),
+  CONSTRAINT `USER_LEDGER_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `USER_LEDGER_ibfk_2` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `CHALLENGE`
+--
+
+DROP TABLE IF EXISTS `CHALLENGE`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `CHALLENGE` (
+  `id` int unsigned NOT NULL AUTO_INCREMENT,
+  `challenger_id` int unsigned NOT NULL,
+  `opponent_id` int unsigned NOT NULL,
+  `season_id` int unsigned NOT NULL,
+  `status_id` int unsigned NOT NULL DEFAULT '1',
+  `challenger_points` int DEFAULT NULL,
+  `opponent_points` int DEFAULT NULL,
+  `winner_id` int unsigned DEFAULT NULL,
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `challenger_id` (`challenger_id`),
+  KEY `opponent_id` (`opponent_id`),
+  KEY `season_id` (`season_id`),
+  CONSTRAINT `CHALLENGE_ibfk_1` FOREIGN KEY (`challenger_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `CHALLENGE_ibfk_2` FOREIGN KEY (`opponent_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `CHALLENGE_ibfk_3` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `CHALLENGE_FIXTURE`
+--
+
+DROP TABLE IF EXISTS `CHALLENGE_FIXTURE`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `CHALLENGE_FIXTURE` (
+  `challenge_id` int unsigned NOT NULL,
+  `fixture_id` int unsigned NOT NULL,
+  PRIMARY KEY (`challenge_id`,`fixture_id`),
+  CONSTRAINT `CHALLENGE_FIXTURE_ibfk_1` FOREIGN KEY (`challenge_id`) REFERENCES `CHALLENGE` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `SCOREBOARD_SNAPSHOT`
+--
+
+DROP TABLE IF EXISTS `SCOREBOARD_SNAPSHOT`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `SCOREBOARD_SNAPSHOT` (
+  `season_id` int unsigned NOT NULL,
+  `user_id` int unsigned NOT NULL,
+  `day` date NOT NULL,
+  `ranking` int unsigned NOT NULL,
+  `points` decimal(12,2) NOT NULL,
+  PRIMARY KEY (`season_id`,`day`,`user_id`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `SCOREBOARD_SNAPSHOT_ibfk_1` FOREIGN KEY (`season_id`) REFERENCES `SEASON` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `SCOREBOARD_SNAPSHOT_ibfk_2` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 /*!40101 SET character_set_client = @saved_cs_client */;
 
@@ -254,6 +430,59 @@ CREATE TABLE `USER_CLUB` (
 ) ENGINE=InnoDB AUTO_INCREMENT=27 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 /*!40101 SET character_set_client = @saved_cs_client */;
 
+--
+-- Table structure for table `USER_GROUP`
+--
+
+DROP TABLE IF EXISTS `USER_GROUP`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_GROUP` (
+  `id` int unsigned NOT NULL AUTO_INCREMENT,
+  `name` varchar(32) NOT NULL,
+  `invite_code` char(8) NOT NULL,
+  `owner_id` int unsigned NOT NULL,
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `invite_code` (`invite_code`),
+  KEY `owner_id` (`owner_id`),
+  CONSTRAINT `USER_GROUP_ibfk_1` FOREIGN KEY (`owner_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `USER_GROUP_LEAGUE`
+--
+
+DROP TABLE IF EXISTS `USER_GROUP_LEAGUE`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_GROUP_LEAGUE` (
+  `group_id` int unsigned NOT NULL,
+  `league_id` int unsigned NOT NULL,
+  PRIMARY KEY (`group_id`,`league_id`),
+  CONSTRAINT `USER_GROUP_LEAGUE_ibfk_1` FOREIGN KEY (`group_id`) REFERENCES `USER_GROUP` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+--
+-- Table structure for table `USER_GROUP_MEMBER`
+--
+
+DROP TABLE IF EXISTS `USER_GROUP_MEMBER`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!50503 SET character_set_client = utf8mb4 */;
+CREATE TABLE `USER_GROUP_MEMBER` (
+  `group_id` int unsigned NOT NULL,
+  `user_id` int unsigned NOT NULL,
+  `datetime` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`group_id`,`user_id`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `USER_GROUP_MEMBER_ibfk_1` FOREIGN KEY (`group_id`) REFERENCES `USER_GROUP` (`id`) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT `USER_GROUP_MEMBER_ibfk_2` FOREIGN KEY (`user_id`) REFERENCES `USER` (`id`) ON DELETE CASCADE ON UPDATE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
 --
 -- Table structure for table `USER_LEAGUE`
 --
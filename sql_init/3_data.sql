@@ -31,7 +31,7 @@ UNLOCK TABLES;
 
 LOCK TABLES `NAVACCESS` WRITE;
 /*!40000 ALTER TABLE `NAVACCESS` DISABLE KEYS */;
-INSERT INTO `NAVACCESS` VALUES (1,'M00010_LEADERBOARD','M3 10h18M3 14h18m-9-4v8m-7 0h14a2 2 0 002-2V8a2 2 0 00-2-2H5a2 2 0 00-2 2v8a2 2 0 002 2z','/leaderboard',1),(2,'M00010_BETS','M9 12l2 2 4-4m6 2a9 9 0 11-18 0 9 9 0 0118 0z','/mybets',NULL),(3,'M00010_ADMIN','M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z','/admin/users',3),(4,'Activation of users',NULL,'/user/activation',NULL),(5,'Deletion of users',NULL,'/user/deletion',NULL),(6,'User modification',NULL,'/user/modification',NULL),(7,'User search',NULL,'/user/search',NULL),(8,'M00010_GAMES','M3.055 11H5a2 2 0 012 2v1a2 2 0 002 2 2 2 0 012 2v2.945M8 3.935V5.5A2.5 2.5 0 0010.5 8h.5a2 2 0 012 2 2 2 0 104 0 2 2 0 012-2h1.064M15 20.488V18a2 2 0 012-2h3.064M21 12a9 9 0 11-18 0 9 9 0 0118 0z','/games',2),(10,'See profile',NULL,'/profile/edit',NULL),(11,'Favorite user\'s leagues',NULL,'/profile/leagues',NULL),(12,'Favorite clubs',NULL,'/profile/clubs',NULL),(13,'Games status update',NULL,'/games/update/status',NULL),(14,'Clubs search',NULL,'/clubs/search',NULL),(15,'Bookmakers',NULL,'/admin/bookmakers',NULL),(16,'Bets on game',NULL,'/games/bet',NULL),(17,'Season choser',NULL,'/admin/seasons',NULL),(18,'Add new season',NULL,'/admin/season/add',NULL),(19,'Set main season',NULL,'/admin/season/set_main',NULL),(20,'Close the season',NULL,'/admin/season/close',NULL),(21,'M00010_NEWS','\r\nM19 20H5a2 2 0 01-2-2V6a2 2 0 012-2h10a2 2 0 012 2v1m2 13a2 2 0 01-2-2V7m2 13a2 2 0 002-2V9a2 2 0 00-2-2h-2m-4-3H9M7 16h6M7 8h6v4H7V8z','/',0);
+INSERT INTO `NAVACCESS` VALUES (1,'M00010_LEADERBOARD','M3 10h18M3 14h18m-9-4v8m-7 0h14a2 2 0 002-2V8a2 2 0 00-2-2H5a2 2 0 00-2 2v8a2 2 0 002 2z','/leaderboard',1),(2,'M00010_BETS','M9 12l2 2 4-4m6 2a9 9 0 11-18 0 9 9 0 0118 0z','/mybets',4),(3,'M00010_ADMIN','M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z','/admin/users',3),(4,'Activation of users',NULL,'/user/activation',NULL),(5,'Deletion of users',NULL,'/user/deletion',NULL),(6,'User modification',NULL,'/user/modification',NULL),(7,'User search',NULL,'/user/search',NULL),(8,'M00010_GAMES','M3.055 11H5a2 2 0 012 2v1a2 2 0 002 2 2 2 0 012 2v2.945M8 3.935V5.5A2.5 2.5 0 0010.5 8h.5a2 2 0 012 2 2 2 0 104 0 2 2 0 012-2h1.064M15 20.488V18a2 2 0 012-2h3.064M21 12a9 9 0 11-18 0 9 9 0 0118 0z','/games',2),(10,'See profile',NULL,'/profile/edit',NULL),(11,'Favorite user\'s leagues',NULL,'/profile/leagues',NULL),(12,'Favorite clubs',NULL,'/profile/clubs',NULL),(13,'Games status update',NULL,'/games/update/status',NULL),(14,'Clubs search',NULL,'/clubs/search',NULL),(15,'Bookmakers',NULL,'/admin/bookmakers',NULL),(16,'Bets on game',NULL,'/games/bet',NULL),(17,'Season choser',NULL,'/admin/seasons',NULL),(18,'Add new season',NULL,'/admin/season/add',NULL),(19,'Set main season',NULL,'/admin/season/set_main',NULL),(20,'Close the season',NULL,'/admin/season/close',NULL),(21,'M00010_NEWS','\r\nM19 20H5a2 2 0 01-2-2V6a2 2 0 012-2h10a2 2 0 012 2v1m2 13a2 2 0 01-2-2V7m2 13a2 2 0 002-2V9a2 2 0 00-2-2h-2m-4-3H9M7 16h6M7 8h6v4H7V8z','/',0),(22,'My bets API',NULL,'/api/mybets',NULL),(23,'Score bets on game',NULL,'/games/bet/score',NULL),(24,'Market bets on game',NULL,'/games/bet/market',NULL),(25,'M00010_ACCUMULATORS','M19 11H5m14 0a2 2 0 012 2v6a2 2 0 01-2 2H5a2 2 0 01-2-2v-6a2 2 0 012-2m14 0V9a2 2 0 00-2-2M5 11V9a2 2 0 012-2m0 0V5a2 2 0 012-2h6a2 2 0 012 2v2M7 7h10','/accumulators',5),(26,'Place accumulator',NULL,'/accumulators/add',NULL),(27,'M00010_GROUPS','M17 20h5v-2a3 3 0 00-5.356-1.857M17 20H7m10 0v-2c0-.656-.126-1.283-.356-1.857M7 20H2v-2a3 3 0 015.356-1.857M7 20v-2c0-.656.126-1.283.356-1.857m0 0a5.002 5.002 0 019.288 0M15 7a3 3 0 11-6 0 3 3 0 016 0zm6 3a2 2 0 11-4 0 2 2 0 014 0zM7 10a2 2 0 11-4 0 2 2 0 014 0z','/groups',6),(28,'Create group',NULL,'/groups/add',NULL),(29,'Join group',NULL,'/groups/join',NULL),(30,'Leave group',NULL,'/groups/leave',NULL),(31,'M00010_CHALLENGES','M13 10V3L4 14h7v7l9-11h-7z','/challenges',7),(32,'Create challenge',NULL,'/challenges/add',NULL),(33,'Answer challenge',NULL,'/challenges/answer',NULL),(34,'Ranking history',NULL,'/leaderboard/history',NULL);
 /*!40000 ALTER TABLE `NAVACCESS` ENABLE KEYS */;
 UNLOCK TABLES;
 
@@ -41,7 +41,7 @@ UNLOCK TABLES;
 
 LOCK TABLES `ROLE_NAVACCESS` WRITE;
 /*!40000 ALTER TABLE `ROLE_NAVACCESS` DISABLE KEYS */;
-INSERT INTO `ROLE_NAVACCESS` VALUES (1,1,1),(3,2,1),(5,3,1),(4,2,2),(6,3,2),(7,3,3),(8,3,4),(9,3,5),(10,3,6),(11,3,7),(12,1,8),(13,2,8),(14,3,8),(18,1,10),(19,2,10),(20,3,10),(21,1,11),(22,2,11),(23,3,11),(24,3,12),(25,2,12),(26,1,12),(27,2,13),(28,3,13),(29,1,14),(30,2,14),(31,3,14),(32,3,15),(33,1,16),(34,2,16),(35,3,16),(37,3,17),(38,3,18),(39,3,19),(40,3,20),(42,1,21),(43,2,21),(44,3,21);
+INSERT INTO `ROLE_NAVACCESS` VALUES (1,1,1),(3,2,1),(5,3,1),(4,2,2),(6,3,2),(7,3,3),(8,3,4),(9,3,5),(10,3,6),(11,3,7),(12,1,8),(13,2,8),(14,3,8),(18,1,10),(19,2,10),(20,3,10),(21,1,11),(22,2,11),(23,3,11),(24,3,12),(25,2,12),(26,1,12),(27,2,13),(28,3,13),(29,1,14),(30,2,14),(31,3,14),(32,3,15),(33,1,16),(34,2,16),(35,3,16),(37,3,17),(38,3,18),(39,3,19),(40,3,20),(42,1,21),(43,2,21),(44,3,21),(45,1,2),(46,1,22),(47,2,22),(48,3,22),(49,1,23),(50,2,23),(51,3,23),(52,1,24),(53,2,24),(54,3,24),(55,1,25),(56,2,25),(57,3,25),(58,1,26),(59,2,26),(60,3,26),(61,1,27),(62,2,27),(63,3,27),(64,1,28),(65,2,28),(66,3,28),(67,1,29),(68,2,29),(69,3,29),(70,1,30),(71,2,30),(72,3,30),(73,1,31),(74,2,31),(75,3,31),(76,1,32),(77,2,32),(78,3,32),(79,1,33),(80,2,33),(81,3,33),(82,1,34),(83,2,34),(84,3,34);
 /*!40000 ALTER TABLE `ROLE_NAVACCESS` ENABLE KEYS */;
 UNLOCK TABLES;
 
@@ -51,7 +51,7 @@ UNLOCK TABLES;
 
 LOCK TABLES `LABEL` WRITE;
 /*!40000 ALTER TABLE `LABEL` DISABLE KEYS */;
-INSERT INTO `LABEL` VALUES (1,'HOME_WELCOME_BACK','Welcome back !'),(2,'HOME_PARAGRAPH','This is some random text right here'),(3,'FORM_LOGIN','Login'),(4,'FORM_PASSWORD','Password'),(5,'FORM_NAME','Name'),(6,'FORM_LANGUAGE','Language'),(7,'FORM_ROLE','Role'),(8,'FORM_ACCESS','Can access'),(9,'FORM_SAVE','Save'),(10,'FORM_DELETE','Delete'),(11,'FORM_EDIT','Edit'),(12,'FORM_ACTIVATION','Activation'),(13,'FORM_ACTIVATE','Activate'),(14,'FORM_DEACTIVATE','Deactivate'),(15,'FORM_DELETION','Deletion'),(16,'COMMON_NEXT','Next'),(17,'COMMON_PREVIOUS','Previous'),(18,'COMMON_SEARCH_LOGIN','Search login'),(19,'SIDE_PERSONNAL_INFOS','Your informations'),(20,'SIDE_FAV_LEAGUES','Your favorite leagues'),(21,'M2001_SIDE_YOUR_INFOS','Your informations'),(22,'M2001_SIDE_YOUR_LEAGUES','Your favorite leagues'),(23,'M2001_SIDE_YOUR_CLUBS','Your favorite clubs'),(24,'M2003_SEARCH_RESULTS','Search results'),(25,'M2003_SEARCH_RESULTS_DESC','Find below the results of the search you just made, click on go back if you want to display your favorite leagues again'),(26,'M2003_SEARCH_RESULTS_NONE','No results are matching your search criteria, please try again with another wording.'),(27,'M2003_TITLE','Your favorite leagues'),(28,'M2003_TITLE_DESC','Find below your favorite leagues'),(29,'M2003_NO_FAV','You don\'t have any favorite league so far, add some by using the country picker first.'),(30,'M2003_SEARCH_ACTION','Search a league'),(31,'M2003_SEARCH_ACTION_DESC','If you want to add or remove a particular league from your profile, you can use the search bar below.'),(32,'M2004_SEARCH_RESULTS','Search results'),(33,'M2004_NO_MATCH','No results are matching your research'),(34,'M2004_YOUR_FAV','Your favorite clubs'),(35,'M2004_YOUR_FAV_DESC','Find below your favorite clbus'),(36,'M2004_NO_FAV','You don\'t have any club so far that has been added to your profile, do a search and mark the clubs you like as favorites.'),(37,'M2004_SEARCH_ACTION','Search for a club'),(38,'M2004_SEARCH_ACTION_DESC','Type the club name you want to do a search for on the search bar below'),(47,'M2004_TITLE','Your favorite clubs'),(49,'M2002_TITLE','Your informations'),(50,'M10010_AET','after extra time'),(51,'M10010_ON_PENS','on pens'),(52,'M10010_STARTS_IN','Starts in'),(53,'COMMON_MINUTES','minutes'),(54,'COMMON_HOURS','hours'),(55,'COMMON_AND','and'),(56,'M10010_RESULT_UNKNOWN','Result unknown for this game'),(57,'M10001_TODAY_NO','No games available for today'),(58,'M10001_YESTERDAY_NO','Yesterday games aren\'t available'),(59,'M10001_TOMOROW_NO','Tomorow games aren\'t available'),(60,'M10002_NO_GAMES','No games available for that day, or you might have not added games to your favorites yet if you aren\'t in see all games mode.'),(61,'M10002_ADD_MORE','To see more games, edit your settings and add clubs or leagues to your favorites'),(62,'M10011_FAVORITE','Favorites only'),(63,'M10011_ALL','All games'),(64,'M10001_TOMOROW_TITLE','Tomorow games'),(65,'M10001_YESTERDAY_TITLE','Yesterday games'),(66,'M10001_TODAY_TITLE','Today games'),(67,'M10001_TITLE','Games'),(68,'M10001_GAME_OF_DAY','Games of the day'),(69,'M00010_LEADERBOARD','Leaderboard'),(70,'M00010_BETS','My bets'),(71,'M00010_ADMIN','Administration'),(72,'M00010_GAMES','Games'),(73,'M30001_TITLE','User management'),(74,'COMMON_GO_BACK','Go back'),(75,'M10010_SEE_MORE','see more'),(76,'M10010_LAST_UPDATED','Last updated on'),(77,'M2003_SEARCH_BAR_LEAGUE','Search a league'),(78,'M2004_SEARCH_BAR_CLUB','Search a club'),(79,'M30002_TITLE','Bookmakers'),(80,'M30002_PICK_A_BM','Use this screen to pick the bookmaker that should be used for the odds :'),(81,'HOME_LATEST_NEWS','Latest news'),(82,'M10011_DEFAULT','Favorites and bets'),(83,'M10011_BETS','Bets only'),(84,'M10011_POTENTIAL_BETS','Potential bets only'),(85,'M30003_TITLE','Seasons'),(86,'M30003_PICK_A_SZN','Pick the season you want to modify.'),(87,'FORM_CLOSE','Close'),(88,'M30003_SET_AS_MAIN','Set as current season'),(89,'M30003_INFO','The main season is prefixed with a star thus be aware that you have to set another season as current if you want to close the current one.'),(90,'M30003_ADD_LEAGUE_PH','Type the new season name right here'),(91,'M30003_ADD_SZN_INFO','You can add a new season by typing its name right below'),(92,'M30003_HEADER_1','Add a new season'),(93,'M30003_HEADER_2','Change a season\'s status'),(95,'M4001_TITLE','Leaderboard'),(96,'M40001_TITLE','Leaderboard'),(97,'COMMON_ALL','All'),(98,'M40001_ALL_SEASONS','All seasons'),(99,'M40001_ALL_TIME_RESULT','All time result'),(100,'M40001_NO_RESULT','There is no result for this season'),(101,'M40001_RANKING','Ranking'),(102,'M4001_POINTS','Points'),(103,'M40001_NUMBER_OF_BETS','Number of bets made'),(104,'M40001_PPB','Points per bet'),(105,'M40001_SEASON','Season'),(106,'M40001_PLAYER','Player'),(107,'M40001_POINTS','Points'),(108,'M00010_NEWS','News'),(109,'HOME_TOP3','Top 3');
+INSERT INTO `LABEL` VALUES (1,'HOME_WELCOME_BACK','Welcome back !'),(2,'HOME_PARAGRAPH','This is some random text right here'),(3,'FORM_LOGIN','Login'),(4,'FORM_PASSWORD','Password'),(5,'FORM_NAME','Name'),(6,'FORM_LANGUAGE','Language'),(7,'FORM_ROLE','Role'),(8,'FORM_ACCESS','Can access'),(9,'FORM_SAVE','Save'),(10,'FORM_DELETE','Delete'),(11,'FORM_EDIT','Edit'),(12,'FORM_ACTIVATION','Activation'),(13,'FORM_ACTIVATE','Activate'),(14,'FORM_DEACTIVATE','Deactivate'),(15,'FORM_DELETION','Deletion'),(16,'COMMON_NEXT','Next'),(17,'COMMON_PREVIOUS','Previous'),(18,'COMMON_SEARCH_LOGIN','Search login'),(19,'SIDE_PERSONNAL_INFOS','Your informations'),(20,'SIDE_FAV_LEAGUES','Your favorite leagues'),(21,'M2001_SIDE_YOUR_INFOS','Your informations'),(22,'M2001_SIDE_YOUR_LEAGUES','Your favorite leagues'),(23,'M2001_SIDE_YOUR_CLUBS','Your favorite clubs'),(24,'M2003_SEARCH_RESULTS','Search results'),(25,'M2003_SEARCH_RESULTS_DESC','Find below the results of the search you just made, click on go back if you want to display your favorite leagues again'),(26,'M2003_SEARCH_RESULTS_NONE','No results are matching your search criteria, please try again with another wording.'),(27,'M2003_TITLE','Your favorite leagues'),(28,'M2003_TITLE_DESC','Find below your favorite leagues'),(29,'M2003_NO_FAV','You don\'t have any favorite league so far, add some by using the country picker first.'),(30,'M2003_SEARCH_ACTION','Search a league'),(31,'M2003_SEARCH_ACTION_DESC','If you want to add or remove a particular league from your profile, you can use the search bar below.'),(32,'M2004_SEARCH_RESULTS','Search results'),(33,'M2004_NO_MATCH','No results are matching your research'),(34,'M2004_YOUR_FAV','Your favorite clubs'),(35,'M2004_YOUR_FAV_DESC','Find below your favorite clbus'),(36,'M2004_NO_FAV','You don\'t have any club so far that has been added to your profile, do a search and mark the clubs you like as favorites.'),(37,'M2004_SEARCH_ACTION','Search for a club'),(38,'M2004_SEARCH_ACTION_DESC','Type the club name you want to do a search for on the search bar below'),(47,'M2004_TITLE','Your favorite clubs'),(49,'M2002_TITLE','Your informations'),(50,'M10010_AET','after extra time'),(51,'M10010_ON_PENS','on pens'),(52,'M10010_STARTS_IN','Starts in'),(53,'COMMON_MINUTES','minutes'),(54,'COMMON_HOURS','hours'),(55,'COMMON_AND','and'),(56,'M10010_RESULT_UNKNOWN','Result unknown for this game'),(57,'M10001_TODAY_NO','No games available for today'),(58,'M10001_YESTERDAY_NO','Yesterday games aren\'t available'),(59,'M10001_TOMOROW_NO','Tomorow games aren\'t available'),(60,'M10002_NO_GAMES','No games available for that day, or you might have not added games to your favorites yet if you aren\'t in see all games mode.'),(61,'M10002_ADD_MORE','To see more games, edit your settings and add clubs or leagues to your favorites'),(62,'M10011_FAVORITE','Favorites only'),(63,'M10011_ALL','All games'),(64,'M10001_TOMOROW_TITLE','Tomorow games'),(65,'M10001_YESTERDAY_TITLE','Yesterday games'),(66,'M10001_TODAY_TITLE','Today games'),(67,'M10001_TITLE','Games'),(68,'M10001_GAME_OF_DAY','Games of the day'),(69,'M00010_LEADERBOARD','Leaderboard'),(70,'M00010_BETS','My bets'),(71,'M00010_ADMIN','Administration'),(72,'M00010_GAMES','Games'),(73,'M30001_TITLE','User management'),(74,'COMMON_GO_BACK','Go back'),(75,'M10010_SEE_MORE','see more'),(76,'M10010_LAST_UPDATED','Last updated on'),(77,'M2003_SEARCH_BAR_LEAGUE','Search a league'),(78,'M2004_SEARCH_BAR_CLUB','Search a club'),(79,'M30002_TITLE','Bookmakers'),(80,'M30002_PICK_A_BM','Use this screen to pick the bookmaker that should be used for the odds :'),(81,'HOME_LATEST_NEWS','Latest news'),(82,'M10011_DEFAULT','Favorites and bets'),(83,'M10011_BETS','Bets only'),(84,'M10011_POTENTIAL_BETS','Potential bets only'),(85,'M30003_TITLE','Seasons'),(86,'M30003_PICK_A_SZN','Pick the season you want to modify.'),(87,'FORM_CLOSE','Close'),(88,'M30003_SET_AS_MAIN','Set as current season'),(89,'M30003_INFO','The main season is prefixed with a star thus be aware that you have to set another season as current if you want to close the current one.'),(90,'M30003_ADD_LEAGUE_PH','Type the new season name right here'),(91,'M30003_ADD_SZN_INFO','You can add a new season by typing its name right below'),(92,'M30003_HEADER_1','Add a new season'),(93,'M30003_HEADER_2','Change a season\'s status'),(95,'M4001_TITLE','Leaderboard'),(96,'M40001_TITLE','Leaderboard'),(97,'COMMON_ALL','All'),(98,'M40001_ALL_SEASONS','All seasons'),(99,'M40001_ALL_TIME_RESULT','All time result'),(100,'M40001_NO_RESULT','There is no result for this season'),(101,'M40001_RANKING','Ranking'),(102,'M4001_POINTS','Points'),(103,'M40001_NUMBER_OF_BETS','Number of bets made'),(104,'M40001_PPB','Points per bet'),(105,'M40001_SEASON','Season'),(106,'M40001_PLAYER','Player'),(107,'M40001_POINTS','Points'),(108,'M00010_NEWS','News'),(109,'HOME_TOP3','Top 3'),(110,'M50001_TITLE','My bets'),(111,'M50001_LEAGUE','League'),(112,'M50001_STATUS','Status'),(113,'M50001_PENDING','Pending'),(114,'M50001_WON','Won'),(115,'M50001_LOST','Lost'),(116,'M50001_FILTER','Filter'),(117,'M50001_PLACED_ON','Placed on'),(118,'M50001_GAME','Game'),(119,'M50001_PICK','Pick'),(120,'M50001_STAKE','Stake'),(121,'M50001_DRAW','Draw'),(122,'M50001_RUNNING_TOTAL','Running total'),(123,'M50001_NO_BETS','You haven\'t made any bet matching these criteria yet'),(124,'M50001_VOID','Void'),(125,'M30003_SETTLEMENT_POLICY','Settlement policy'),(126,'M30003_REGULAR_TIME','Result after 90 minutes'),(127,'M30003_ADVANCING','Team that advances'),(128,'M10010_SETTLED_ON_REGULAR_TIME','Settled on the 90 minutes result'),(129,'M10010_SETTLED_ON_ADVANCING','Settled on the team that advances'),(130,'M10010_PREDICT_SCORE','Predict the score'),(131,'M10010_PICK_OVER','Over 2.5'),(132,'M10010_PICK_UNDER','Under 2.5'),(133,'M10010_PICK_BTTS','Both teams score'),(134,'M10010_PICK_NOT_BTTS','Not both teams score'),(135,'M10010_PICK_HOME_OR_DRAW','Home or draw'),(136,'M10010_PICK_HOME_OR_AWAY','Home or away'),(137,'M10010_PICK_DRAW_OR_AWAY','Draw or away'),(138,'M10010_ODDS_MOVEMENT','Odds movement since opening'),(139,'M00010_ACCUMULATORS','Accumulators'),(140,'M50002_TITLE','My accumulators'),(141,'M50002_NEW','New accumulator'),(142,'M50002_NOT_ENOUGH_GAMES','Not enough games are open to bets to place an accumulator'),(143,'M50002_RESET','Reset'),(144,'M50002_PLACE','Place the accumulator'),(145,'M50002_MY_ACCUMULATORS','My accumulators'),(146,'M50002_LEGS','Games'),(147,'M50002_NO_ACCUMULATORS','No accumulator has been placed yet'),(148,'M30003_STARTING_BALANCE','Starting balance'),(149,'M10010_AMOUNT','Amount'),(150,'M40001_BALANCE','Balance'),(153,'M30003_SCORING_RULE','Scoring rule'),(154,'M30003_ODDS_WEIGHTED','Points weighted by the odds'),(155,'M30003_FLAT_POINTS','Same points for every right pick'),(156,'M30003_WRONG_PICK_PENALTY','Points weighted by the odds, wrong picks penalized'),(157,'M00010_GROUPS','Groups'),(158,'M40002_TITLE','My groups'),(159,'M40002_MY_GROUPS','My groups'),(160,'M40002_INVITE_CODE','Invite code'),(161,'M40002_MEMBERS','Members'),(162,'M40002_COMPETITIONS','Competitions'),(163,'M40002_ALL_COMPETITIONS','All competitions'),(164,'M40002_LEAVE','Leave'),(165,'M40002_NO_GROUPS','You aren\'t a member of any group yet, create one or join one with an invite code.'),(166,'M40002_JOIN','Join a group'),(167,'M40002_CREATE','Create a group'),(168,'M40002_RESTRICT_TO','Restrict the group to these competitions (all competitions if none is checked) :'),(169,'M40002_GROUP','Group'),(170,'M00010_CHALLENGES','Challenges'),(171,'M50003_TITLE','My challenges'),(172,'M50003_MY_CHALLENGES','My challenges'),(173,'M50003_CHALLENGER','Challenger'),(174,'M50003_OPPONENT','Opponent'),(175,'M50003_ACCEPT','Accept'),(176,'M50003_DECLINE','Decline'),(177,'M50003_ACCEPTED','Accepted'),(178,'M50003_DECLINED','Declined'),(179,'M50003_DRAW','Draw'),(180,'M50003_NO_CHALLENGES','You haven\'t been involved in any challenge yet'),(181,'M50003_NEW','Challenge a user'),(182,'M50003_PICK_GAMES','Pick the games the challenge stands on :'),(183,'M50003_CHALLENGE','Challenge'),(184,'M50003_NO_GAMES','No game is open to bets to make a challenge'),(185,'M10010_JOKER','Joker (double points)'),(186,'M10010_CROWD_PICKS','Share of the picks, with your groups\' members who made them'),(187,'M40001_HIT_RATE','Hit rate'),(188,'M40001_AVERAGE_ODDS','Average odds'),(189,'M40001_LONGEST_STREAK','Longest winning streak'),(190,'M40001_BREAKDOWN','Breakdown'),(191,'M40001_SEE_BREAKDOWN','By pick and competition'),(192,'M40001_WHOLE_PERIOD','Whole period'),(193,'M40001_THIS_WEEK','This week'),(194,'M40001_LAST_WEEK','Last week'),(195,'M40001_THIS_MONTH','This month'),(196,'M40001_LAST_SEVEN_DAYS','Last 7 days'),(197,'M40001_LAST_THIRTY_DAYS','Last 30 days'),(198,'M40001_RANK_CHANGE','Evolution'),(199,'M40001_HISTORY','Ranking history'),(200,'M40003_TITLE','Ranking history'),(201,'M40003_DAY','Day'),(202,'M40003_NO_SNAPSHOT','No ranking has been recorded for this season yet'),(203,'M30003_TIE_BREAK_POLICY','Tie-break policy'),(204,'M30003_FEWER_BETS_FIRST','Fewer bets made, then points per bet, then earliest bet'),(205,'M30003_HIGHER_PPB_FIRST','Points per bet, then fewer bets made, then earliest bet'),(206,'M30003_EARLIEST_BET_FIRST','Earliest bet, then fewer bets made, then points per bet'),(207,'M30003_NO_TIE_BREAK','No tie-break, equal users share the rank'),(208,'M40001_TIE_BREAK','Ties broken by'),(209,'M50003_EXPIRED','Expired');
 /*!40000 ALTER TABLE `LABEL` ENABLE KEYS */;
 UNLOCK TABLES;
 
@@ -81,7 +81,7 @@ UNLOCK TABLES;
 
 LOCK TABLES `TRANSLATION` WRITE;
 /*!40000 ALTER TABLE `TRANSLATION` DISABLE KEYS */;
-INSERT INTO `TRANSLATION` VALUES (3,1,2,'Bon retour'),(4,2,2,'Bon retour parmis nous'),(5,3,2,'Identifiant'),(6,4,2,'Mot de passe'),(7,5,2,'Nom'),(8,6,2,'Langage'),(9,7,2,'Role'),(10,8,2,'Peut accèder à l’application'),(11,9,2,'Sauvegarder'),(12,10,2,'Supprimer'),(13,11,2,'Editer'),(14,12,2,'Activation'),(15,13,2,'Activer'),(16,14,2,'Désactivation'),(17,15,2,'Suppression'),(18,16,2,'Suivant'),(19,17,2,'Précèdent'),(20,18,2,'Rechercher un nom d’utilisateur'),(21,19,2,'Vos informations'),(22,20,2,'Vos ligues favorites'),(23,21,2,'Vos informations'),(24,22,2,'Vos ligues favorites'),(25,23,2,'Vos clubs favoris'),(26,24,2,'Résultat de la recherche'),(27,25,2,'Trouvez ci-dessous les résultats de la recherche, cliquez sur retour si vous souhaitez retournez à l’écran précèdent'),(28,26,2,'Aucun résultat n’a été trouvé pour votre recherche, réessayez avec une autre formulation'),(29,27,2,'Vos ligues favorites'),(30,28,2,'Trouvez ci-dessous vos ligues favorites'),(31,29,2,'Vous n’avez pas de ligues dans vos favoris pour le moment, vous pouvez en ajoutez en utilisant la barre de recherche à droite'),(32,30,2,'Rechercher une ligue'),(33,31,2,'Si vous souhaitez ajouter ou supprimer une ligue de vos favoris, utilisez la barre de recherche ci-dessous'),(34,32,2,'Résultat de la recherche'),(35,33,2,'Aucun résultat n’a été trouvé pour votre recherche, réessayez avec une autre formulation'),(36,34,2,'Vos clubs favoris'),(37,35,2,'Trouvez ci-dessous vos clubs favoris'),(38,36,2,'Vous n’avez pas de clubs dans vos favoris pour le moment, vous pouvez en ajoutez en utilisant la barre de recherche à droite'),(39,37,2,'Rechercher un club'),(40,38,2,'Tapez le nom du club que vous souhaitez rechercher'),(41,47,2,'Vos clubs favoris'),(42,49,2,'Vos informations'),(43,50,2,'après temps additionnel'),(44,51,2,'sur pénalties'),(45,52,2,'Commence dans'),(46,53,2,'minutes'),(47,54,2,'heures'),(48,55,2,'et'),(49,56,2,'Résultat non connu pour ce match'),(50,57,2,'Pas de matchs disponibles pour ce jour'),(51,58,2,'Les matchs d’hier ne sont pas disponibles'),(52,59,2,'Les matchs de demain ne sont pas disponibles'),(53,60,2,'Pas de matchs trouvés pour ce jour selon les critères que vous avez choisi'),(54,61,2,'Ajoutez des clubs ou des ligues dans vos favoris pour potentiellement voir plus de résultats de recherche'),(55,62,2,'Seulement les favoris'),(56,63,2,'Tous les matchs'),(57,64,2,'Matchs de demain'),(58,65,2,'Matchs d’hier'),(59,66,2,'Matchs d’aujourd’hui'),(60,67,2,'Matchs'),(61,68,2,'Matchs du jour'),(62,69,2,'Tableau des scores'),(63,70,2,'Mes paris'),(64,71,2,'Administration'),(65,72,2,'Matchs'),(66,73,2,'Gestion des utilisateurs'),(67,74,2,'Retour en arrière'),(68,75,2,'voir plus'),(69,76,2,'Dernière mise à jour le'),(70,77,2,'Rechercher une ligue'),(71,78,2,'Chercher un club'),(72,80,2,'Utilisez le champ ci-dessous pour choisir un bookmaker pour l\'application :'),(73,81,2,'Sur le fil'),(74,83,2,'Seulement les paris'),(75,82,2,'Favoris et paris'),(76,84,2,'Seulement les paris potentiels'),(77,85,2,'Saisons'),(78,87,2,'Clôturer '),(79,88,2,'Définir comme la saison courante'),(80,89,2,'La saison courante a pour préfixe une étoile, veillez à définir une nouvelle saison courante si vous souhaitez la clôturer'),(81,90,2,'Tapez le nom de la nouvelle saison ici'),(82,91,2,'Vous pouvez ajouter une nouvelle saison en tapant son nom ci-dessous'),(83,92,2,'Ajouter une nouvelle saison'),(92,93,2,'Changer le statut d\'une saison'),(93,86,2,'Choisissez la saison que vous souhaitez modifier'),(94,97,2,'Tous'),(95,98,2,'Toute saison confondue'),(96,99,2,'Résultats pour toutes les saisons confondues'),(97,105,2,'Saison'),(98,106,2,'Joueur'),(99,108,2,'Actus');
+INSERT INTO `TRANSLATION` VALUES (3,1,2,'Bon retour'),(4,2,2,'Bon retour parmis nous'),(5,3,2,'Identifiant'),(6,4,2,'Mot de passe'),(7,5,2,'Nom'),(8,6,2,'Langage'),(9,7,2,'Role'),(10,8,2,'Peut accèder à l’application'),(11,9,2,'Sauvegarder'),(12,10,2,'Supprimer'),(13,11,2,'Editer'),(14,12,2,'Activation'),(15,13,2,'Activer'),(16,14,2,'Désactivation'),(17,15,2,'Suppression'),(18,16,2,'Suivant'),(19,17,2,'Précèdent'),(20,18,2,'Rechercher un nom d’utilisateur'),(21,19,2,'Vos informations'),(22,20,2,'Vos ligues favorites'),(23,21,2,'Vos informations'),(24,22,2,'Vos ligues favorites'),(25,23,2,'Vos clubs favoris'),(26,24,2,'Résultat de la recherche'),(27,25,2,'Trouvez ci-dessous les résultats de la recherche, cliquez sur retour si vous souhaitez retournez à l’écran précèdent'),(28,26,2,'Aucun résultat n’a été trouvé pour votre recherche, réessayez avec une autre formulation'),(29,27,2,'Vos ligues favorites'),(30,28,2,'Trouvez ci-dessous vos ligues favorites'),(31,29,2,'Vous n’avez pas de ligues dans vos favoris pour le moment, vous pouvez en ajoutez en utilisant la barre de recherche à droite'),(32,30,2,'Rechercher une ligue'),(33,31,2,'Si vous souhaitez ajouter ou supprimer une ligue de vos favoris, utilisez la barre de recherche ci-dessous'),(34,32,2,'Résultat de la recherche'),(35,33,2,'Aucun résultat n’a été trouvé pour votre recherche, réessayez avec une autre formulation'),(36,34,2,'Vos clubs favoris'),(37,35,2,'Trouvez ci-dessous vos clubs favoris'),(38,36,2,'Vous n’avez pas de clubs dans vos favoris pour le moment, vous pouvez en ajoutez en utilisant la barre de recherche à droite'),(39,37,2,'Rechercher un club'),(40,38,2,'Tapez le nom du club que vous souhaitez rechercher'),(41,47,2,'Vos clubs favoris'),(42,49,2,'Vos informations'),(43,50,2,'après temps additionnel'),(44,51,2,'sur pénalties'),(45,52,2,'Commence dans'),(46,53,2,'minutes'),(47,54,2,'heures'),(48,55,2,'et'),(49,56,2,'Résultat non connu pour ce match'),(50,57,2,'Pas de matchs disponibles pour ce jour'),(51,58,2,'Les matchs d’hier ne sont pas disponibles'),(52,59,2,'Les matchs de demain ne sont pas disponibles'),(53,60,2,'Pas de matchs trouvés pour ce jour selon les critères que vous avez choisi'),(54,61,2,'Ajoutez des clubs ou des ligues dans vos favoris pour potentiellement voir plus de résultats de recherche'),(55,62,2,'Seulement les favoris'),(56,63,2,'Tous les matchs'),(57,64,2,'Matchs de demain'),(58,65,2,'Matchs d’hier'),(59,66,2,'Matchs d’aujourd’hui'),(60,67,2,'Matchs'),(61,68,2,'Matchs du jour'),(62,69,2,'Tableau des scores'),(63,70,2,'Mes paris'),(64,71,2,'Administration'),(65,72,2,'Matchs'),(66,73,2,'Gestion des utilisateurs'),(67,74,2,'Retour en arrière'),(68,75,2,'voir plus'),(69,76,2,'Dernière mise à jour le'),(70,77,2,'Rechercher une ligue'),(71,78,2,'Chercher un club'),(72,80,2,'Utilisez le champ ci-dessous pour choisir un bookmaker pour l\'application :'),(73,81,2,'Sur le fil'),(74,83,2,'Seulement les paris'),(75,82,2,'Favoris et paris'),(76,84,2,'Seulement les paris potentiels'),(77,85,2,'Saisons'),(78,87,2,'Clôturer '),(79,88,2,'Définir comme la saison courante'),(80,89,2,'La saison courante a pour préfixe une étoile, veillez à définir une nouvelle saison courante si vous souhaitez la clôturer'),(81,90,2,'Tapez le nom de la nouvelle saison ici'),(82,91,2,'Vous pouvez ajouter une nouvelle saison en tapant son nom ci-dessous'),(83,92,2,'Ajouter une nouvelle saison'),(92,93,2,'Changer le statut d\'une saison'),(93,86,2,'Choisissez la saison que vous souhaitez modifier'),(94,97,2,'Tous'),(95,98,2,'Toute saison confondue'),(96,99,2,'Résultats pour toutes les saisons confondues'),(97,105,2,'Saison'),(98,106,2,'Joueur'),(99,108,2,'Actus'),(100,110,2,'Mes paris'),(101,111,2,'Ligue'),(102,112,2,'Statut'),(103,113,2,'En attente'),(104,114,2,'Gagné'),(105,115,2,'Perdu'),(106,116,2,'Filtrer'),(107,117,2,'Placé le'),(108,118,2,'Match'),(109,119,2,'Choix'),(110,120,2,'Cote'),(111,121,2,'Match nul'),(112,122,2,'Total cumulé'),(113,123,2,'Vous n\'avez fait aucun pari correspondant à ces critères'),(114,124,2,'Annulé'),(115,125,2,'Règle de validation'),(116,126,2,'Résultat après 90 minutes'),(117,127,2,'Équipe qualifiée'),(118,128,2,'Validé sur le résultat après 90 minutes'),(119,129,2,'Validé sur l\'équipe qualifiée'),(120,130,2,'Pronostiquer le score'),(121,131,2,'Plus de 2,5'),(122,132,2,'Moins de 2,5'),(123,133,2,'Les deux équipes marquent'),(124,134,2,'Les deux équipes ne marquent pas'),(125,135,2,'Domicile ou nul'),(126,136,2,'Domicile ou extérieur'),(127,137,2,'Nul ou extérieur'),(128,138,2,'Évolution de la cote depuis l\'ouverture'),(129,139,2,'Combinés'),(130,140,2,'Mes combinés'),(131,141,2,'Nouveau combiné'),(132,142,2,'Pas assez de matchs ouverts aux paris pour placer un combiné'),(133,143,2,'Réinitialiser'),(134,144,2,'Placer le combiné'),(135,145,2,'Mes combinés'),(136,146,2,'Matchs'),(137,147,2,'Aucun combiné n\'a encore été placé'),(138,148,2,'Solde de départ'),(139,149,2,'Montant'),(140,150,2,'Solde'),(143,153,2,'Règle de calcul des points'),(144,154,2,'Points pondérés par la cote'),(145,155,2,'Mêmes points pour chaque bon pronostic'),(146,156,2,'Points pondérés par la cote, mauvais pronostics pénalisés'),(147,157,2,'Groupes'),(148,158,2,'Mes groupes'),(149,159,2,'Mes groupes'),(150,160,2,'Code d\'invitation'),(151,161,2,'Membres'),(152,162,2,'Compétitions'),(153,163,2,'Toutes les compétitions'),(154,164,2,'Quitter'),(155,165,2,'Vous n\'êtes membre d\'aucun groupe pour le moment, créez-en un ou rejoignez-en un avec un code d\'invitation.'),(156,166,2,'Rejoindre un groupe'),(157,167,2,'Créer un groupe'),(158,168,2,'Restreindre le groupe à ces compétitions (toutes les compétitions si aucune n\'est cochée) :'),(159,169,2,'Groupe'),(160,170,2,'Défis'),(161,171,2,'Mes défis'),(162,172,2,'Mes défis'),(163,173,2,'Challenger'),(164,174,2,'Adversaire'),(165,175,2,'Accepter'),(166,176,2,'Refuser'),(167,177,2,'Accepté'),(168,178,2,'Refusé'),(169,179,2,'Égalité'),(170,180,2,'Vous n\'avez participé à aucun défi pour le moment'),(171,181,2,'Défier un utilisateur'),(172,182,2,'Choisissez les matchs sur lesquels porte le défi :'),(173,183,2,'Défier'),(174,184,2,'Aucun match n\'est ouvert aux paris pour lancer un défi'),(175,185,2,'Joker (points doublés)'),(176,186,2,'Répartition des pronostics, avec les membres de vos groupes qui les ont faits'),(177,187,2,'Taux de réussite'),(178,188,2,'Cote moyenne'),(179,189,2,'Plus longue série gagnante'),(180,190,2,'Détail'),(181,191,2,'Par pronostic et compétition'),(182,192,2,'Toute la période'),(183,193,2,'Cette semaine'),(184,194,2,'La semaine dernière'),(185,195,2,'Ce mois-ci'),(186,196,2,'7 derniers jours'),(187,197,2,'30 derniers jours'),(188,198,2,'Évolution'),(189,199,2,'Historique du classement'),(190,200,2,'Historique du classement'),(191,201,2,'Jour'),(192,202,2,'Aucun classement n\'a encore été enregistré pour cette saison'),(193,203,2,'Départage des égalités'),(194,204,2,'Moins de paris, puis points par pari, puis pari le plus ancien'),(195,205,2,'Points par pari, puis moins de paris, puis pari le plus ancien'),(196,206,2,'Pari le plus ancien, puis moins de paris, puis points par pari'),(197,207,2,'Aucun départage, les ex-aequo partagent le rang'),(198,208,2,'Égalités départagées par'),(199,209,2,'Expiré');
 /*!40000 ALTER TABLE `TRANSLATION` ENABLE KEYS */;
 UNLOCK TABLES;
 